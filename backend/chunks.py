@@ -0,0 +1,107 @@
+from pathlib import Path
+from typing import Dict, List, Tuple
+
+from config import get_config
+from probe import derived_name, transcode, conform_scale, proxy_scale
+
+# Hits from neighbouring chunks that land within this many seconds of each
+# other in the original are the same moment found twice in the overlap
+DUPLICATE_TOLERANCE = 1.0
+
+
+def needs_chunking(info: Dict) -> bool:
+    """Check whether a probed file is longer than the indexer accepts and chunking is enabled."""
+    max_duration = get_config()["validation"]["max_duration"]
+    return (
+        get_config()["chunking"]["enabled"]
+        and bool(max_duration)
+        and (info.get("duration") or 0) > max_duration
+    )
+
+
+def plan_chunks(duration: float, chunk_duration: float, overlap: float) -> List[Tuple[float, float]]:
+    """
+    Split a duration into overlapping chunks.
+
+    Returns:
+        List[Tuple[float, float]]: The start offset and length of each chunk in
+        seconds; each chunk starts overlap seconds before the previous one ends
+    """
+    step = max(chunk_duration - overlap, 1.0)
+    chunks = []
+    start = 0.0
+    while True:
+        length = min(chunk_duration, duration - start)
+        chunks.append((round(start, 3), round(length, 3)))
+        if start + length >= duration:
+            return chunks
+        start += step
+
+
+def parent_video_id(content_hash: str) -> str:
+    """The catalog ID of a chunked file, which is not itself in the index."""
+    return f"chunked-{content_hash[:16]}"
+
+
+def chunk_path(path: str, temp_dir: Path, index: int) -> Path:
+    """Where one chunk of a long file is written, unique per source path."""
+    return temp_dir / "chunks" / f"{derived_name(path)}_{index:03d}.mp4"
+
+
+def make_chunk(path: str, temp_dir: Path, index: int, start: float, length: float) -> str:
+    """
+    Transcode one chunk of a long file for indexing.
+
+    Chunks are re-encoded rather than stream-copied so each one starts exactly
+    at its offset; they use the proxy size when proxies are enabled.
+
+    Returns:
+        str: The path of the chunk file
+    """
+    config = get_config()
+    if config["proxy"]["enabled"]:
+        scale, crf = proxy_scale(), config["proxy"]["crf"]
+    else:
+        scale, crf = conform_scale(), config["validation"]["conform_crf"]
+    return transcode(path, chunk_path(path, temp_dir, index), scale, crf, "veryfast", start=start, length=length)
+
+
+def translate_clips(clips: List[Dict], chunks: Dict[str, Dict]) -> List[Dict]:
+    """
+    Map search hits in a chunk back onto the timeline of the original file.
+
+    Args:
+        clips (List[Dict]): Search results
+        chunks (Dict[str, Dict]): Catalog entries of chunks, keyed by video_id,
+            with the parent_id and offset of each
+
+    Returns:
+        List[Dict]: The clips with chunk hits moved to their parent video and
+        offset into the original; the chunk's own ID is kept as
+        chunk_video_id, and a moment found in two overlapping chunks is only
+        kept once, with the better score
+    """
+    translated: List[Dict] = []
+    for clip in clips:
+        chunk = chunks.get(clip["video_id"])
+        if not chunk:
+            translated.append(clip)
+            continue
+        clip = dict(
+            clip,
+            video_id=chunk["parent_id"],
+            start_time=round(clip["start_time"] + chunk["offset"], 3),
+            end_time=round(clip["end_time"] + chunk["offset"], 3),
+            chunk_video_id=clip["video_id"],
+        )
+        duplicate = next((
+            i for i, other in enumerate(translated)
+            if other["video_id"] == clip["video_id"]
+            and abs(other["start_time"] - clip["start_time"]) <= DUPLICATE_TOLERANCE
+            and abs(other["end_time"] - clip["end_time"]) <= DUPLICATE_TOLERANCE
+        ), None)
+        if duplicate is None:
+            translated.append(clip)
+        elif clip["score"] > translated[duplicate]["score"]:
+            translated[duplicate] = clip
+    return translated
@@ -0,0 +1,212 @@
+import copy
+import os
+import tomllib
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from logging_config import get_logger
+
+logger = get_logger("config")
+
+PROJECT_CONFIG_NAME = "reduct.toml"
+USER_CONFIG_PATH = Path(os.getenv("XDG_CONFIG_HOME", Path.home() / ".config")) / "reduct" / PROJECT_CONFIG_NAME
+ENV_PREFIX = "REDUCT_"
+
+DEFAULTS: Dict[str, Dict[str, Any]] = {
+    "paths": {
+        "output_dir": "edited/videos",
+        "temp_dir": "temp",
+        "projects_file": "",  # Registry of named projects; defaults to projects.json next to the user config
+    },
+    "catalog": {
+        "backend": "sqlite",  # sqlite, json or mongodb
+        "path": "",  # SQLite file or JSON folder; defaults to catalog.db / catalog/ next to the user config
+        "mongo_uri": "",  # For the mongodb backend; defaults to MONGO_URI
+        "mongo_database": "videos",
+    },
+    "models": {
+        "analysis": "gemini-2.0-flash",  # Prompt analysis in VideoEditor.analyze_prompt
+        "planner": "gemini-2.0-flash",  # Edit plan generation and session summaries
+        "index": "marengo2.7",  # Twelvelabs engine for indexes created with `project create`
+    },
+    "search": {
+        "min_score": 0.7,  # Scores above this are considered "high"
+        "page_limit": 5,
+    },
+    "upload": {
+        "extensions": [".mp4", ".mov", ".mkv", ".avi", ".webm", ".m4v", ".mts"],
+        "recursive": False,  # Descend into subdirectories of directory inputs
+        "max_concurrent": 4,  # Files uploaded and indexed at the same time
+        "retries": 3,  # Retries for transient API failures, per call
+        "retry_backoff": 2.0,  # Seconds before the first retry, doubled on each attempt
+        "timeout": 3600,  # Seconds allowed per file, including indexing; 0 for no limit
+        "poll_interval": 5,  # Seconds between indexing status checks
+        # Seconds without an update after which `resume` takes over another machine's unfinished job
+        "stale_after": 900,
+    },
+    "validation": {
+        # Files are probed with ffprobe before uploading; the limits match what Twelvelabs accepts
+        "enabled": True,
+        "min_duration": 4,  # Seconds
+        "max_duration": 7200,  # Seconds; 0 for no limit
+        "min_width": 360,
+        "min_height": 360,
+        "max_width": 3840,
+        "max_height": 2160,
+        "max_size_mb": 2048,  # 0 for no limit
+        "video_codecs": ["h264", "hevc", "vp8", "vp9", "av1", "mpeg4", "prores"],  # Empty to allow any
+        "audio_codecs": ["aac", "mp3", "opus", "vorbis", "ac3", "eac3", "flac", "pcm_s16le", "pcm_s24le"],
+        "require_audio": False,
+        "conform": False,  # Transcode files with fixable problems to an H.264/AAC mezzanine instead of rejecting them
+        "conform_crf": 20,
+    },
+    "chunking": {
+        # Files longer than validation.max_duration are split and each chunk is indexed separately
+        "enabled": True,
+        "chunk_duration": 3600,  # Seconds per chunk
+        "overlap": 30,  # Seconds shared by neighbouring chunks, so moments at a boundary are still found
+    },
+    "proxy": {
+        "enabled": False,  # Index a low-resolution proxy instead of the original; renders still use the original
+        "short_side": 480,  # Pixels on the shorter side of the proxy picture
+        "crf": 28,
+    },
+    "shots": {
+        "enabled": True,  # Detect shot boundaries with PySceneDetect when a video is indexed
+        "threshold": 27.0,  # ContentDetector threshold; lower finds more cuts
+        "min_scene_len": 15,  # Frames
+        "snap_tolerance": 1.0,  # Seconds a search result's start or end may move to reach a shot boundary
+    },
+    "thumbnails": {
+        "enabled": True,  # Extract thumbnails at each search result's start, midpoint and end
+        "width": 320,  # Pixels; the height keeps the aspect ratio
+    },
+    "watch": {
+        "folders": ["uploads"],  # Folders checked by `reduct watch`
+        "interval": 5,  # Seconds between folder scans
+        "settle_seconds": 10,  # A file must stop changing for this long before it is uploaded
+        "status_file": "",  # JSON status for other tools; defaults to <temp_dir>/watch_status.json
+    },
+    "render": {
+        "vcodec": "libx264",
+        "acodec": "aac",
+        "audio_bitrate": "192k",
+    },
+}
+
+_config: Optional[Dict[str, Dict[str, Any]]] = None
+_config_path: Optional[Path] = None
+
+
+def find_project_config(start: Optional[Path] = None) -> Optional[Path]:
+    """Find reduct.toml in the working directory or the closest parent that has one."""
+    directory = (start or Path.cwd()).resolve()
+    for candidate in [directory, *directory.parents]:
+        path = candidate / PROJECT_CONFIG_NAME
+        if path.is_file():
+            return path
+    return None
+
+
+def merge(base: Dict, overrides: Dict) -> Dict:
+    """Recursively merge overrides into a copy of base."""
+    merged = copy.deepcopy(base)
+    for key, value in overrides.items():
+        if isinstance(value, dict) and isinstance(merged.get(key), dict):
+            merged[key] = merge(merged[key], value)
+        else:
+            merged[key] = value
+    return merged
+
+
+def coerce(value: str, default: Any) -> Any:
+    """Convert an environment variable string to the type of the setting's default."""
+    if isinstance(default, bool):
+        return value.strip().lower() in ("1", "true", "yes", "on")
+    if isinstance(default, int):
+        return int(value)
+    if isinstance(default, float):
+        return float(value)
+    if isinstance(default, list):
+        return [item.strip() for item in value.split(",") if item.strip()]
+    return value
+
+
+def env_overrides(config: Dict) -> Dict:
+    """
+    Read REDUCT_<SECTION>_<KEY> environment variables, e.g. REDUCT_SEARCH_MIN_SCORE=0.5.
+
+    Only settings that already exist in the config are overridden, and values
+    are converted to the type of the existing setting.
+    """
+    overrides: Dict[str, Dict[str, Any]] = {}
+    for name, value in os.environ.items():
+        if not name.startswith(ENV_PREFIX):
+            continue
+        section, _, key = name[len(ENV_PREFIX):].lower().partition("_")
+        if section not in config or not isinstance(config[section], dict) or key not in config[section]:
+            continue
+        try:
+            overrides.setdefault(section, {})[key] = coerce(value, config[section][key])
+        except ValueError:
+            logger.warning(f"Ignoring {name}: {value!r} is not a valid value")
+    return overrides
+
+
+def read_toml(path: Path) -> Dict:
+    """Read one config file, returning an empty config if it is invalid."""
+    try:
+        with open(path, "rb") as f:
+            return tomllib.load(f)
+    except (OSError, tomllib.TOMLDecodeError) as e:
+        logger.warning(f"Ignoring config file {path}: {str(e)}")
+        return {}
+
+
+def config_files(path: Optional[Path] = None) -> List[Path]:
+    """The config files that apply, lowest precedence first."""
+    files = []
+    if USER_CONFIG_PATH.is_file():
+        files.append(USER_CONFIG_PATH)
+    explicit = path or (Path(os.environ["REDUCT_CONFIG"]) if os.getenv("REDUCT_CONFIG") else None)
+    project = explicit or find_project_config()
+    if project:
+        files.append(project)
+    return files
+
+
+def load_config(path: Optional[Path] = None) -> Dict[str, Dict[str, Any]]:
+    """
+    Load the effective configuration.
+
+    Layers, lowest precedence first: built-in defaults, the user config
+    (~/.config/reduct/reduct.toml), the project config (the closest reduct.toml,
+    or the file given with --config / REDUCT_CONFIG), then REDUCT_* environment
+    variables.
+    """
+    config = copy.deepcopy(DEFAULTS)
+    for config_file in config_files(path):
+        logger.debug(f"Loading config file {config_file}")
+        config = merge(config, read_toml(config_file))
+    return merge(config, env_overrides(config))
+
+
+def set_config_path(path: Optional[str]) -> None:
+    """Use an explicit project config file instead of searching for reduct.toml."""
+    global _config, _config_path
+    _config_path = Path(path) if path else None
+    _config = None
+
+
+def get_config() -> Dict[str, Dict[str, Any]]:
+    """Get the effective configuration, loading it on first use."""
+    global _config
+    if _config is None:
+        _config = load_config(_config_path)
+    return _config
+
+
+def override_config(overrides: Dict[str, Dict[str, Any]]) -> None:
+    """Layer settings over the effective configuration for this run, e.g. the selected project's directories."""
+    global _config
+    _config = merge(get_config(), overrides)
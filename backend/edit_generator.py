@@ -2,46 +2,177 @@ import ffmpeg
 import uuid
 from pathlib import Path
 import os
+import json
+import shlex
+import subprocess
+from typing import Callable, List, Dict, Optional
+from logging_config import get_logger
+from config import get_config
 
-def generate_ffmpeg_from_plan(edit_plan: dict, input_path: str, output_path: str = None):
-    if not output_path:
-        output_path = f"output_{uuid.uuid4().hex[:8]}.mp4"
+logger = get_logger("edit_generator")
 
-    # Create temp directory for segments
-    temp_dir = Path("temp")
-    temp_dir.mkdir(parents=True, exist_ok=True)
+
+def save_edit_plan(edit_plan: dict, plan_path: str, source: Optional[str] = None) -> None:
+    """Write an edit plan to disk so it can be re-rendered later without calling Gemini."""
+    plan = dict(edit_plan)
+    if source and "source" not in plan:
+        plan["source"] = source
+    path = Path(plan_path)
+    path.parent.mkdir(parents=True, exist_ok=True)
+    with open(path, "w") as f:
+        json.dump(plan, f, indent=2)
+
+
+def load_edit_plan(plan_path: str) -> dict:
+    """Read an edit plan from disk and check that it has an actions list."""
+    with open(plan_path) as f:
+        edit_plan = json.load(f)
+    if not isinstance(edit_plan, dict) or not isinstance(edit_plan.get("actions"), list):
+        raise ValueError(f"{plan_path} is not an edit plan: expected an object with an \"actions\" array")
+    return edit_plan
+
+
+def time_to_seconds(time_str: str) -> float:
+    """Convert an "HH:MM:SS" timestamp (seconds may be fractional) to seconds."""
+    h, m, s = map(float, time_str.split(':'))
+    return h * 3600 + m * 60 + s
+
+
+def seconds_to_timestamp(seconds: float) -> str:
+    """Convert seconds to an "HH:MM:SS" timestamp, keeping fractional seconds."""
+    h, rem = divmod(round(max(seconds, 0.0), 3), 3600)
+    m, s = divmod(rem, 60)
+    s = round(s, 3)
+    if s == int(s):
+        return f"{int(h):02d}:{int(m):02d}:{int(s):02d}"
+    return f"{int(h):02d}:{int(m):02d}:{s:06.3f}".rstrip("0")
+
+
+def clamp_plan(edit_plan: dict, durations: Dict[str, float], default_video_id: Optional[str] = None) -> List[str]:
+    """
+    Keep every trim inside its source video, changing the plan in place.
+
+    Trim ends past the end of their video are pulled back to its duration, and
+    trims that start at or after the end are dropped along with their concat
+    entry. Trims without a video_id are checked against default_video_id, and
+    trims whose video has no known duration are left alone.
+
+    Returns:
+        List[str]: A description of each change, for logging
+    """
+    changes = []
+    dropped = set()
+    for action in edit_plan.get("actions", []):
+        if action.get("type") != "trim":
+            continue
+        video_id = action.get("video_id") or default_video_id
+        duration = durations.get(video_id)
+        if not duration:
+            continue
+        try:
+            start, end = time_to_seconds(action["start"]), time_to_seconds(action["end"])
+        except (KeyError, ValueError):
+            continue
+        if start >= duration:
+            dropped.add(action.get("output"))
+            changes.append(
+                f"Dropped {action.get('output')}: starts at {action['start']}, "
+                f"after the end of {video_id} ({seconds_to_timestamp(duration)})"
+            )
+        elif end > duration:
+            action["end"] = seconds_to_timestamp(duration)
+            changes.append(
+                f"Clamped {action.get('output')}: end {seconds_to_timestamp(end)} is past the end of "
+                f"{video_id}, now {action['end']}"
+            )
+
+    if dropped:
+        actions = []
+        for action in edit_plan["actions"]:
+            if action.get("type") == "trim" and action.get("output") in dropped:
+                continue
+            if action.get("type") == "concat":
+                segments = sorted(action.get("segments", []), key=lambda x: x["position"])
+                kept = [segment for segment in segments if segment["file"] not in dropped]
+                action["segments"] = [dict(segment, position=i) for i, segment in enumerate(kept)]
+            actions.append(action)
+        edit_plan["actions"] = actions
+    return changes
+
+
+def retarget_plan(edit_plan: dict, video_id: Optional[str] = None) -> None:
+    """
+    Point every trim at another source, changing the plan in place.
+
+    Trims get video_id, or lose their video_id so they cut from the input file
+    when it is None. Raises ValueError if the plan cuts from more than one
+    video, since there is no telling which of them the new source replaces.
+    """
+    trims = [action for action in edit_plan.get("actions", []) if action.get("type") == "trim"]
+    video_ids = sorted({action["video_id"] for action in trims if action.get("video_id")})
+    if len(video_ids) > 1:
+        raise ValueError(
+            f"The plan cuts from {len(video_ids)} videos ({', '.join(video_ids)}), "
+            "so a single --source can't replace them; leave out --source"
+        )
+    for action in trims:
+        if video_id:
+            action["video_id"] = video_id
+        else:
+            action.pop("video_id", None)
+
+
+def build_plan_steps(
+    edit_plan: dict, input_path: str, output_path: str, temp_dir: Path, sources: Optional[Dict[str, str]] = None
+) -> List[Dict]:
+    """
+    Walk an edit plan and build the ffmpeg step for every trim and concat action.
+
+    Nothing is executed and no files are touched, so the same steps can be run
+    or just printed for review. Trims that carry a video_id are cut from
+    sources[video_id]; all others are cut from input_path.
+
+    Returns:
+        List[Dict]: Steps in execution order with their kind, ffmpeg stream,
+        output file and duration in seconds
+    """
+    steps = []
+    segment_durations = {}
+    render_config = get_config()["render"]
 
     # Process trim actions first
-    segment_files = []
     for action in edit_plan.get("actions", []):
         if action["type"] == "trim":
             start = action.get("start")
             end = action.get("end")
             output = action.get("output")
-            
-            if start and end and output:
-                # Convert timestamps to seconds
-                def time_to_seconds(time_str):
-                    h, m, s = map(float, time_str.split(':'))
-                    return h * 3600 + m * 60 + s
 
+            if start and end and output:
                 start_sec = time_to_seconds(start)
                 end_sec = time_to_seconds(end)
-                
+
                 # Create output path for this segment
                 segment_path = temp_dir / output
-                
+
+                trim_input = (sources or {}).get(action.get("video_id"), input_path)
+
                 # Trim the segment with both video and audio
-                stream = ffmpeg.input(input_path)
+                stream = ffmpeg.input(trim_input)
                 stream = ffmpeg.trim(stream, start=start_sec, end=end_sec).setpts('PTS-STARTPTS')
                 # Use appropriate codecs for filtered output
-                stream = ffmpeg.output(stream, str(segment_path), 
-                                     acodec='aac',  # Use AAC for audio
-                                     vcodec='libx264',  # Use H.264 for video
-                                     audio_bitrate='192k')  # Set reasonable audio bitrate
-                ffmpeg.run(stream, overwrite_output=True)
-                
-                segment_files.append(str(segment_path))
+                stream = ffmpeg.output(stream, str(segment_path),
+                                     acodec=render_config["acodec"],
+                                     vcodec=render_config["vcodec"],
+                                     audio_bitrate=render_config["audio_bitrate"])
+
+                duration = max(end_sec - start_sec, 0.0)
+                segment_durations[output] = duration
+                steps.append({
+                    "kind": "trim",
+                    "stream": stream,
+                    "output": str(segment_path),
+                    "duration": duration,
+                })
 
     # Process concat action
     for action in edit_plan.get("actions", []):
@@ -49,34 +180,157 @@ def generate_ffmpeg_from_plan(edit_plan: dict, input_path: str, output_path: str
             segments = action.get("segments", [])
             if segments:
                 # Sort segments by position
-                segments.sort(key=lambda x: x["position"])
-                
-                # Create concat file
+                segments = sorted(segments, key=lambda x: x["position"])
+
                 concat_file = temp_dir / "concat_list.txt"
-                with open(concat_file, "w") as f:
-                    for segment in segments:
-                        file_path = temp_dir / segment["file"]
-                        if file_path.exists():
-                            # Use absolute path in concat file
-                            f.write(f"file '{file_path.absolute()}'\n")
-                
                 # Use concat demuxer to join segments with audio
                 stream = ffmpeg.input(str(concat_file), format='concat', safe=0)
                 # Use appropriate codecs for final output
-                stream = ffmpeg.output(stream, output_path, 
-                                     acodec='aac',  # Use AAC for audio
-                                     vcodec='libx264',  # Use H.264 for video
-                                     audio_bitrate='192k')  # Set reasonable audio bitrate
-                ffmpeg.run(stream, overwrite_output=True)
-                
-                # Clean up concat file
-                concat_file.unlink()
+                stream = ffmpeg.output(stream, output_path,
+                                     acodec=render_config["acodec"],
+                                     vcodec=render_config["vcodec"],
+                                     audio_bitrate=render_config["audio_bitrate"])
+
+                steps.append({
+                    "kind": "concat",
+                    "stream": stream,
+                    "output": output_path,
+                    "concat_file": str(concat_file),
+                    "segments": [str(temp_dir / segment["file"]) for segment in segments],
+                    "duration": sum(segment_durations.get(segment["file"], 0.0) for segment in segments),
+                })
+
+    return steps
+
+
+def plan_render_seconds(edit_plan: dict, sources: Optional[Dict[str, str]] = None) -> float:
+    """Total seconds of output ffmpeg will encode for a plan, across trims and the final concat."""
+    steps = build_plan_steps(edit_plan, "", "", Path(get_config()["paths"]["temp_dir"]), sources)
+    return sum(step["duration"] for step in steps)
+
+
+def describe_plan(
+    edit_plan: dict, input_path: str, output_path: str = None, sources: Optional[Dict[str, str]] = None
+) -> Dict:
+    """
+    Describe what rendering an edit plan would do without running ffmpeg.
+
+    Returns:
+        Dict: The ffmpeg command lines, the temp files that would be created,
+        the output path and the expected output duration in seconds
+    """
+    if not output_path:
+        output_path = f"output_{uuid.uuid4().hex[:8]}.mp4"
+
+    temp_dir = Path(get_config()["paths"]["temp_dir"])
+    steps = build_plan_steps(edit_plan, input_path, output_path, temp_dir, sources)
+    temp_files = []
+    for step in steps:
+        if step["kind"] == "trim":
+            temp_files.append(step["output"])
+        else:
+            temp_files.append(step["concat_file"])
+
+    concat_steps = [step for step in steps if step["kind"] == "concat"]
+    return {
+        "input_path": input_path,
+        "output_path": output_path,
+        "commands": [shlex.join(ffmpeg_command(step["stream"])) for step in steps],
+        "temp_files": temp_files,
+        "expected_duration": concat_steps[-1]["duration"] if concat_steps else 0.0,
+    }
+
+
+def ffmpeg_command(stream) -> List[str]:
+    """The exact command line a render step runs, shared by renders and dry runs."""
+    args = ffmpeg.compile(stream, overwrite_output=True)
+    return args[:1] + ["-progress", "pipe:1", "-nostats", "-loglevel", "error"] + args[1:]
+
+
+def run_with_progress(stream, on_progress: Callable[[float], None]) -> None:
+    """
+    Run an ffmpeg stream, reporting seconds of output written as it goes.
+
+    Progress comes from ffmpeg's -progress output, which reports out_time_us
+    (microseconds of output encoded so far) several times a second.
+    """
+    args = ffmpeg_command(stream)
+    process = subprocess.Popen(args, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True)
+    for line in process.stdout:
+        key, _, value = line.strip().partition("=")
+        if key in ("out_time_us", "out_time_ms") and value.isdigit():
+            # out_time_ms is also in microseconds, despite its name
+            on_progress(int(value) / 1_000_000)
+    stderr = process.stderr.read()
+    if process.wait() != 0:
+        raise ffmpeg.Error("ffmpeg", None, stderr.encode())
+
+
+def generate_ffmpeg_from_plan(
+    edit_plan: dict,
+    input_path: str,
+    output_path: str = None,
+    sources: Optional[Dict[str, str]] = None,
+    on_progress: Optional[Callable[[float, str], None]] = None,
+):
+    """
+    Render an edit plan with ffmpeg.
+
+    If on_progress is given it is called with the seconds of output rendered
+    so far across all steps and a short description of the current step. The
+    total to expect is plan_render_seconds(...) for the same plan.
+    """
+    if not output_path:
+        output_path = f"output_{uuid.uuid4().hex[:8]}.mp4"
+
+    # Create temp directory for segments
+    temp_dir = Path(get_config()["paths"]["temp_dir"])
+    temp_dir.mkdir(parents=True, exist_ok=True)
+
+    segment_files = []
+    steps = build_plan_steps(edit_plan, input_path, output_path, temp_dir, sources)
+    completed = 0.0
+
+    def run_step(step: Dict, description: str) -> None:
+        if on_progress is None:
+            run_with_progress(step["stream"], lambda seconds: None)
+            return
+        on_progress(completed, description)
+        run_with_progress(
+            step["stream"],
+            lambda seconds: on_progress(completed + min(seconds, step["duration"]), description),
+        )
+
+    for i, step in enumerate(steps):
+        description = f"step {i+1}/{len(steps)}: {step['kind']}"
+        if step["kind"] == "trim":
+            run_step(step, description)
+            segment_files.append(step["output"])
+
+        elif step["kind"] == "concat":
+            # Create concat file
+            concat_file = Path(step["concat_file"])
+            with open(concat_file, "w") as f:
+                for segment in step["segments"]:
+                    file_path = Path(segment)
+                    if file_path.exists():
+                        # Use absolute path in concat file
+                        f.write(f"file '{file_path.absolute()}'\n")
+
+            run_step(step, description)
+
+            # Clean up concat file
+            concat_file.unlink()
+
+        completed += step["duration"]
+        if on_progress:
+            on_progress(completed, description)
 
     # Clean up segment files
     for file_path in segment_files:
         try:
             Path(file_path).unlink()
         except Exception as e:
-            print(f"Warning: Could not delete temporary file {file_path}: {str(e)}")
+            logger.warning(f"Could not delete temporary file {file_path}: {str(e)}")
 
     return output_path
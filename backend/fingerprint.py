@@ -0,0 +1,49 @@
+import hashlib
+import os
+from typing import Dict, List, Optional
+
+import ffmpeg
+
+from logging_config import get_logger
+
+logger = get_logger("fingerprint")
+
+CHUNK_SIZE = 1024 * 1024
+
+
+def probe_duration(path: str) -> Optional[float]:
+    """Read a video's duration with ffprobe, or None if it can't be probed."""
+    try:
+        return float(ffmpeg.probe(path)["format"]["duration"])
+    except (ffmpeg.Error, KeyError, ValueError, OSError) as e:
+        logger.debug(f"Could not probe duration of {path}: {str(e)}")
+        return None
+
+
+def fingerprint_file(path: str) -> Dict:
+    """
+    Fingerprint a video so identical content can be recognized before uploading.
+
+    Returns:
+        Dict: The SHA-256 of the file contents, its size in bytes and its
+        duration in seconds (None if ffprobe can't read it)
+    """
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for chunk in iter(lambda: f.read(CHUNK_SIZE), b""):
+            digest.update(chunk)
+    return {
+        "content_hash": digest.hexdigest(),
+        "size": os.path.getsize(path),
+        "duration": probe_duration(path),
+    }
+
+
+def find_duplicates(videos: List[Dict]) -> Dict[str, List[str]]:
+    """Group catalog entries that share a content hash, keyed by hash, listing their video IDs."""
+    groups: Dict[str, List[str]] = {}
+    for video in videos:
+        content_hash = video.get("content_hash")
+        if content_hash:
+            groups.setdefault(content_hash, []).append(video["video_id"])
+    return {content_hash: ids for content_hash, ids in groups.items() if len(ids) > 1}
@@ -0,0 +1,84 @@
+import difflib
+import json
+import uuid
+from datetime import datetime
+from typing import Dict, List
+
+
+def new_edit_id() -> str:
+    """A sortable ID for a history entry, e.g. 20250101_120000_ab12cd."""
+    return datetime.now().strftime("%Y%m%d_%H%M%S_") + uuid.uuid4().hex[:6]
+
+
+def describe_clip(clip: Dict) -> str:
+    return f"{clip['video_id']} {clip['start_time']:.2f}-{clip['end_time']:.2f}s (score {clip.get('score', 0):.2f})"
+
+
+def edit_lines(edit: Dict) -> List[str]:
+    """The parts of an edit worth comparing, as lines for a diff."""
+    comparable = {
+        "prompt": edit.get("prompt"),
+        "analysis": edit.get("analysis"),
+        "clips": [describe_clip(clip) for clip in edit.get("clips", [])],
+        "plan": edit.get("plan"),
+        "sources": edit.get("sources"),
+        "output_path": edit.get("output_path"),
+    }
+    return json.dumps(comparable, indent=2, sort_keys=True).splitlines()
+
+
+def diff_edits(old: Dict, new: Dict) -> List[str]:
+    """A unified diff of two history entries; empty if they match."""
+    return list(difflib.unified_diff(
+        edit_lines(old), edit_lines(new), fromfile=old["edit_id"], tofile=new["edit_id"], lineterm=""
+    ))
+
+
+def print_history(edits: List[Dict]) -> None:
+    """Print one line per edit, most recent first."""
+    if not edits:
+        print("\nNo edits recorded yet.")
+        return
+    print("\nEdit history:")
+    for edit in edits:
+        replay = f" (replay of {edit['replay_of']})" if edit.get("replay_of") else ""
+        print(f"\n{edit['edit_id']} [{edit['status']}]{replay} {edit.get('created_at', '')}")
+        print(f"  Prompt: {edit.get('prompt') or ''}")
+        if edit.get("output_path"):
+            print(f"  Output: {edit['output_path']}")
+
+
+def print_edit(edit: Dict) -> None:
+    """Print everything recorded about one edit."""
+    print(f"\nEdit {edit['edit_id']} [{edit['status']}]")
+    print(f"Created: {edit.get('created_at', '')}")
+    if edit.get("replay_of"):
+        print(f"Replay of: {edit['replay_of']}")
+    if edit.get("session_id"):
+        print(f"Session: {edit['session_id']}")
+    print(f"Prompt: {edit.get('prompt') or ''}")
+    analysis = edit.get("analysis")
+    if analysis:
+        print(f"Search queries: {', '.join(analysis.get('search_queries', []))}")
+        print(f"Editing actions: {', '.join(map(str, analysis.get('editing_actions', [])))}")
+    clips = edit.get("clips", [])
+    print(f"\nClips found: {len(clips)}")
+    for i, clip in enumerate(clips):
+        print(f"  {i+1}. {describe_clip(clip)}")
+    plan = edit.get("plan")
+    if plan:
+        print(f"\nPlan: {len(plan.get('actions', []))} action(s)")
+        print(json.dumps(plan, indent=2))
+    if edit.get("sources"):
+        print("\nSources:")
+        for video_id, path in edit["sources"].items():
+            print(f"  {video_id}: {path}")
+    if edit.get("input_path"):
+        print(f"Input: {edit['input_path']}")
+    if edit.get("output_path"):
+        print(f"Output: {edit['output_path']}")
+    timings = edit.get("timings", {})
+    if timings:
+        print("Timings: " + ", ".join(f"{stage} {seconds:.1f}s" for stage, seconds in timings.items()))
+    for error in edit.get("errors", []):
+        print(f"Error: {error}")
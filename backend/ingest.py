@@ -0,0 +1,96 @@
+import os
+from glob import glob
+from pathlib import Path
+from typing import List, Dict, Optional, Tuple
+
+from config import get_config
+
+
+def has_glob_pattern(path: str) -> bool:
+    """Check whether a path contains glob wildcards."""
+    return any(char in path for char in "*?[")
+
+
+def is_video_file(path: Path, extensions: List[str]) -> bool:
+    """Check a file's extension against the configured video extensions."""
+    return path.is_file() and path.suffix.lower() in extensions
+
+
+def expand_video_paths(
+    inputs: List[str], recursive: Optional[bool] = None, extensions: Optional[List[str]] = None
+) -> Tuple[List[str], List[str]]:
+    """
+    Expand files, directories and glob patterns into a list of video files.
+
+    Args:
+        inputs (List[str]): Paths as typed by the user; may be files, directories
+            or glob patterns (use ** with recursive patterns)
+        recursive (bool, optional): Descend into subdirectories of directory inputs
+            (default: upload.recursive from config)
+        extensions (List[str], optional): Video extensions to keep
+            (default: upload.extensions from config)
+
+    Returns:
+        Tuple[List[str], List[str]]: Absolute video paths in input order without
+        duplicates, and the inputs that did not match any video
+    """
+    upload_config = get_config()["upload"]
+    if recursive is None:
+        recursive = upload_config["recursive"]
+    extensions = [ext.lower() for ext in (extensions or upload_config["extensions"])]
+
+    videos: List[str] = []
+    unmatched: List[str] = []
+    seen = set()
+    for raw in inputs:
+        # Remove any surrounding quotes if present
+        pattern = os.path.expanduser(raw.strip().strip("\"'"))
+        # Existing paths win over patterns so names like "take [1].mp4" still work
+        if os.path.isdir(pattern):
+            directory = Path(pattern)
+            candidates = sorted(directory.rglob("*") if recursive else directory.iterdir())
+        elif os.path.exists(pattern) or not has_glob_pattern(pattern):
+            candidates = [Path(pattern)]
+        else:
+            candidates = sorted(Path(match) for match in glob(pattern, recursive=True))
+
+        matched = [path for path in candidates if is_video_file(path, extensions)]
+        if not matched:
+            unmatched.append(raw)
+        for path in matched:
+            absolute = os.path.abspath(path)
+            if absolute not in seen:
+                seen.add(absolute)
+                videos.append(absolute)
+    return videos, unmatched
+
+
+def format_size(size: int) -> str:
+    """Format a byte count for display."""
+    if size < 1024:
+        return f"{size} B"
+    for unit in ["KB", "MB", "GB"]:
+        size /= 1024
+        if size < 1024:
+            return f"{size:.1f} {unit}"
+    return f"{size / 1024:.1f} TB"
+
+
+def summarize_uploads(paths: List[str]) -> Dict:
+    """Count the files and bytes about to be uploaded, grouped by directory."""
+    directories: Dict[str, Dict] = {}
+    total_size = 0
+    for path in paths:
+        size = os.path.getsize(path)
+        total_size += size
+        entry = directories.setdefault(os.path.dirname(path), {"files": 0, "size": 0})
+        entry["files"] += 1
+        entry["size"] += size
+    return {"files": len(paths), "size": total_size, "directories": directories}
+
+
+def print_upload_summary(summary: Dict) -> None:
+    """Print what will be uploaded before the upload starts."""
+    print(f"\n{summary['files']} video(s) to upload, {format_size(summary['size'])} in total:")
+    for directory, entry in summary["directories"].items():
+        print(f"  {directory}: {entry['files']} file(s), {format_size(entry['size'])}")
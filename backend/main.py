@@ -4,149 +4,766 @@ import argparse
 from datetime import datetime
 from typing import List, Dict, Tuple, Optional
 import subprocess
-from pymongo import MongoClient
 from pathlib import Path
 import shutil
+import socket
 import sys
 import asyncio
-from dataclasses import dataclass
+import time
+from contextlib import contextmanager, redirect_stdout
+from dataclasses import asdict, dataclass
 from enum import Enum
 
-from twelve import upload_video, client, INDEX_ID, search_video
+from twelve import (
+    create_upload_task, get_task, is_transient_error, error_status_code, INDEX_ID, search_video, list_index_videos,
+    delete_index_video, create_index,
+)
 from process_results import ClipProcessor
 from prompt import generate_prompt
-from edit_generator import generate_ffmpeg_from_plan
+from edit_generator import (
+    generate_ffmpeg_from_plan, describe_plan, save_edit_plan, load_edit_plan, plan_render_seconds, clamp_plan,
+    retarget_plan,
+)
+from progress import UploadProgress, RenderProgress, format_duration
+from fingerprint import fingerprint_file, find_duplicates
+from review import review_plan, print_search_results
+from session import EditSession, merge_clips, list_sessions
+from history import new_edit_id, diff_edits, print_history, print_edit
+from ingest import expand_video_paths, summarize_uploads, print_upload_summary
+from watch import watch_folders
+from shots import detect_shots, snap_clips
+from thumbnails import clip_thumbnails
+from chunks import needs_chunking, plan_chunks, parent_video_id, make_chunk, translate_clips
+from reconcile import FileFinder, find_mismatches, print_mismatches
+from probe import ProbeError, probe_video, check_video, conform_video, make_proxy, describe_technical
 from google import genai
 from dotenv import load_dotenv
+from logging_config import get_logger, setup_logging
+from config import get_config, set_config_path, config_files
+from store import open_store
+from projects import Project, load_projects, get_project, check_new_project, create_project, activate_project
+
+logger = get_logger("main")
 
 # Load environment variables
 load_dotenv()
 GEMINI_API_KEY = os.getenv("GEMINI_API_KEY")
 gemini_client = genai.Client(api_key=GEMINI_API_KEY)
-db_client = MongoClient(os.getenv("MONGO_URI"))
-db = db_client["videos"]  # Changed database name to "videos"
-print(f"Connected to MongoDB database: {db.name}")  # Debug log
 
 
 class VideoStatus(Enum):
     PENDING = "pending"
+    CONFORMING = "conforming"
+    PROXYING = "proxying"
+    SPLITTING = "splitting"
     UPLOADING = "uploading"
     INDEXING = "indexing"
     READY = "ready"
+    TIMED_OUT = "timed_out"  # Gave up waiting, but the task exists and `resume` can finish it
     ERROR = "error"
 
 
+# Upload outcomes reported as failures of the command that ran them
+FAILED_STATUSES = (VideoStatus.ERROR.value, VideoStatus.TIMED_OUT.value)
+
+
 @dataclass
 class VideoMetadata:
     path: str
     task_id: Optional[str]
     status: VideoStatus
     error: Optional[str] = None
+    video_id: Optional[str] = None
+    duplicate: bool = False
+    fingerprint: Optional[Dict] = None
+    probe: Optional[Dict] = None
+    proxy_path: Optional[str] = None
+    # For files indexed as chunks: the offset, duration and task_id (None until uploaded) of each
+    chunk_tasks: Optional[List[Dict]] = None
+
+    @property
+    def resumable(self) -> bool:
+        """Whether every indexing task was created, so indexing can finish without this process."""
+        if self.chunk_tasks:
+            return all(chunk["task_id"] for chunk in self.chunk_tasks)
+        return bool(self.task_id)
+
+
+# Twelvelabs task statuses mapped onto our own upload states
+TASK_STATUSES = {
+    "validating": VideoStatus.UPLOADING,
+    "pending": VideoStatus.INDEXING,
+    "queued": VideoStatus.INDEXING,
+    "indexing": VideoStatus.INDEXING,
+    "ready": VideoStatus.READY,
+    "failed": VideoStatus.ERROR,
+}
+
+
+@contextmanager
+def timed(timings: Dict[str, float], stage: str):
+    """Record how long a stage of a command took, in seconds."""
+    started = time.perf_counter()
+    try:
+        yield
+    finally:
+        timings[stage] = round(time.perf_counter() - started, 3)
+
+
+def upload_summary(metadata: VideoMetadata) -> Dict:
+    """Describe the outcome of a single upload."""
+    return {
+        "path": metadata.path,
+        "status": metadata.status.value,
+        "task_id": metadata.task_id,
+        "video_id": metadata.video_id,
+        "error": metadata.error,
+        "duplicate": metadata.duplicate,
+    }
+
+
+def process_alive(pid: int) -> bool:
+    """Check whether a process is running on this machine."""
+    try:
+        os.kill(pid, 0)
+    except ProcessLookupError:
+        return False
+    except PermissionError:
+        return True  # Running, but owned by another user
+    return True
+
+
+def job_in_progress(job: Dict) -> bool:
+    """
+    Check whether another process may still be working on an upload job.
+
+    A job started on this machine is in progress while its process is alive;
+    one started elsewhere (or before owners were recorded) while it was
+    updated within upload.stale_after seconds. Timed-out jobs were given up
+    on by their owner and are never in progress.
+    """
+    if job.get("status") == VideoStatus.TIMED_OUT.value or job.get("owner_pid") == os.getpid():
+        return False
+    # os.kill can't probe a process without signalling it on Windows
+    if job.get("owner_host") == socket.gethostname() and job.get("owner_pid") and os.name != "nt":
+        return process_alive(job["owner_pid"])
+    updated_at = job.get("updated_at")
+    if isinstance(updated_at, str):
+        updated_at = datetime.fromisoformat(updated_at)
+    if not updated_at:
+        return False
+    return (datetime.utcnow() - updated_at).total_seconds() < get_config()["upload"]["stale_after"]
+
+
+def log_upload_summary(summaries: List[Dict], elapsed: float) -> None:
+    """Log one summary line for a batch of uploads, followed by each failure."""
+    ready = [summary for summary in summaries if summary["status"] == VideoStatus.READY.value]
+    reused = [summary for summary in ready if summary["duplicate"]]
+    failed = [summary for summary in summaries if summary["status"] in FAILED_STATUSES]
+    logger.info(
+        f"Uploads finished in {elapsed:.0f}s: {len(ready)} ready ({len(reused)} reused), "
+        f"{len(failed)} failed, {len(summaries)} total"
+    )
+    for summary in failed:
+        logger.error(f"  {summary['path']}: {summary['error']}")
 
 
 class VideoEditor:
-    def __init__(self):
-        self.processor = ClipProcessor()
-        self.output_dir = Path("edited/videos")
-        self.temp_dir = Path("temp")
+    def __init__(self, project: Optional[Project] = None):
+        self.project = project
+        self.index_id = project.index_id if project else INDEX_ID
+        self.processor = ClipProcessor(self.index_id)
+        paths_config = get_config()["paths"]
+        self.output_dir = Path(paths_config["output_dir"])
+        self.temp_dir = Path(paths_config["temp_dir"])
         self.clips_dir = self.temp_dir / "clips"
+        self.sessions_dir = self.temp_dir / "sessions"
+        self.thumbnails_dir = self.temp_dir / "thumbnails"
         self.video_metadata: Dict[str, VideoMetadata] = {}
+        self.upload_probes: Dict[str, Dict] = {}  # Probed to size upload timeouts, reused by the upload
         self.video_id_to_path: Dict[str, str] = {}  # Map video_id to original file path
-        self.metadata_collection = db["metadata"]  # MongoDB collection for video metadata
-        print(f"Using collection: {self.metadata_collection.name}")  # Debug log
+        self.upload_progress: Optional[UploadProgress] = None
+        # Each project keeps its catalog in its own collections
+        prefix = project.collection_prefix if project else ""
+        self.catalog = open_store(f"{prefix}metadata", "video_id")  # Video metadata, keyed by video ID
+        self.jobs = open_store(f"{prefix}jobs", "path")  # Upload jobs, so they survive a crash
+        self.history = open_store(f"{prefix}history", "edit_id")  # Every edit run, so it can be replayed
+        logger.debug(f"Using catalog: {self.catalog.name} ({get_config()['catalog']['backend']})")
 
         # Create necessary directories
         self.output_dir.mkdir(parents=True, exist_ok=True)
         self.temp_dir.mkdir(parents=True, exist_ok=True)
         self.clips_dir.mkdir(parents=True, exist_ok=True)
 
-    def list_uploaded_videos(self) -> List[Dict]:
-        """List all videos that have been uploaded."""
-        return list(self.metadata_collection.find({}, {"_id": 0}))
+    def list_uploaded_videos(self, tag: Optional[str] = None) -> List[Dict]:
+        """List all videos that have been uploaded, or only those with a tag."""
+        if tag:
+            return self.catalog.query_by_tag(tag)
+        return self.catalog.list()
+
+    def tag_video(self, video_id: str, add: List[str], remove: List[str]) -> List[str]:
+        """Add and remove tags on a catalog entry, returning its tags."""
+        metadata = self.get_video_metadata(video_id)
+        if not metadata:
+            raise KeyError(f"Video {video_id} is not in the catalog")
+        tags = [tag for tag in metadata.get("tags", []) if tag not in remove]
+        tags.extend(tag for tag in dict.fromkeys(add) if tag not in tags)
+        self.catalog.upsert(video_id, {"tags": tags})
+        return tags
 
     def check_video_exists(self, video_id: str) -> bool:
         """Check if a video exists in our database."""
-        return self.metadata_collection.find_one({"video_id": video_id}) is not None
+        return self.catalog.get(video_id) is not None
+
+    def find_by_fingerprint(self, fingerprint: Dict) -> Optional[Dict]:
+        """Find an already indexed video with identical content."""
+        return next(
+            (entry for entry in self.catalog.query_by_hash(fingerprint["content_hash"])
+             if entry.get("size") == fingerprint["size"]),
+            None,
+        )
 
-    def save_video_metadata(self, video_id: str, original_path: str) -> None:
-        """Save video metadata to MongoDB."""
+    def save_video_metadata(
+        self,
+        video_id: str,
+        original_path: str,
+        fingerprint: Optional[Dict] = None,
+        technical: Optional[Dict] = None,
+        shots: Optional[List[float]] = None,
+        proxy_path: Optional[str] = None,
+    ) -> None:
+        """Save video metadata to the catalog, probing the file for its technical details if none are given."""
         try:
             metadata = {
                 "video_id": video_id,
                 "original_path": original_path,
                 "uploaded_at": datetime.utcnow()
             }
-            print(f"\nSaving metadata to MongoDB:")  # Debug log
-            print(f"Video ID: {video_id}")
-            print(f"Original Path: {original_path}")
-            
-            result = self.metadata_collection.update_one(
-                {"video_id": video_id},
-                {"$set": metadata},
-                upsert=True
-            )
-            
-            if result.upserted_id:
-                print(f"New document created with ID: {result.upserted_id}")
-            elif result.modified_count > 0:
-                print(f"Existing document updated")
-            else:
-                print(f"No changes made to document")
-                
+            if fingerprint:
+                metadata.update(fingerprint)
+            if technical is None:
+                technical = self.probe_technical(original_path)
+            if technical:
+                metadata["technical"] = technical
+            if shots is not None:
+                metadata["shots"] = shots
+            if proxy_path:
+                # The proxy is what was indexed; original_path stays the render source
+                metadata["proxy_path"] = proxy_path
+            logger.debug(f"Saving metadata to the catalog: {video_id} -> {original_path}")
+            self.catalog.upsert(video_id, metadata)
+
             # Verify the save
-            saved_doc = self.metadata_collection.find_one({"video_id": video_id})
+            saved_doc = self.catalog.get(video_id)
             if saved_doc:
-                print("Successfully verified metadata in database")
+                logger.debug("Successfully verified metadata in database")
             else:
-                print("Warning: Could not verify metadata in database")
+                logger.warning("Could not verify metadata in database")
                 
         except Exception as e:
-            print(f"Error saving metadata to MongoDB: {str(e)}")
+            logger.error(f"Error saving metadata to the catalog: {str(e)}")
             raise
 
+    def probe_technical(self, path: str) -> Optional[Dict]:
+        """Probe a file's technical details, or None if ffprobe can't read it."""
+        try:
+            return probe_video(path)
+        except ProbeError as e:
+            logger.warning(f"Could not read technical metadata of {path}: {str(e)}")
+            return None
+
+    def get_technical_metadata(self, video_id: str) -> Optional[Dict]:
+        """
+        Get a video's technical metadata from the catalog.
+
+        Videos indexed before technical metadata was recorded are probed on
+        first use, and the result is saved so they only get probed once.
+        """
+        metadata = self.get_video_metadata(video_id)
+        if not metadata:
+            return None
+        if metadata.get("technical"):
+            return metadata["technical"]
+        if not os.path.exists(metadata.get("original_path", "")):
+            return None
+        technical = self.probe_technical(metadata["original_path"])
+        if technical:
+            self.catalog.upsert(video_id, {"technical": technical})
+        return technical
+
+    def video_details(self, video_ids: List[str]) -> Dict[str, Dict]:
+        """Duration, frame rate and resolution of each video, for the planner."""
+        details = {}
+        for video_id in video_ids:
+            technical = self.get_technical_metadata(video_id)
+            if technical and technical.get("duration"):
+                details[video_id] = {
+                    "duration": round(technical["duration"], 3),
+                    "fps": technical.get("fps"),
+                    "resolution": f"{technical.get('width')}x{technical.get('height')}",
+                }
+        return details
+
+    def get_shots(self, video_id: str) -> Optional[List[float]]:
+        """A video's shot boundaries in seconds, if they were detected when it was indexed."""
+        metadata = self.get_video_metadata(video_id)
+        return metadata.get("shots") if metadata else None
+
+    async def search_clips(self, query: str, thumbnails: bool = True) -> List[Dict]:
+        """
+        Search the index and snap each result to the nearest shot boundaries of its video.
+
+        Thumbnails are extracted for each clip when enabled, unless thumbnails
+        is False (e.g. for dry runs, which write no media files).
+        """
+        # search_video is synchronous, so it runs in a worker thread
+        clips = await asyncio.to_thread(search_video, query, self.index_id)
+        chunks = {}
+        for video_id in {clip["video_id"] for clip in clips}:
+            metadata = self.get_video_metadata(video_id)
+            if metadata and metadata.get("parent_id"):
+                chunks[video_id] = metadata
+        clips = translate_clips(clips, chunks)
+        video_ids = list(dict.fromkeys(clip["video_id"] for clip in clips))
+        shots = {video_id: self.get_shots(video_id) for video_id in video_ids}
+        shots = {video_id: boundaries for video_id, boundaries in shots.items() if boundaries is not None}
+        durations = {video_id: details["duration"] for video_id, details in self.video_details(list(shots)).items()}
+        clips = snap_clips(clips, shots, durations)
+        if thumbnails and get_config()["thumbnails"]["enabled"]:
+            await asyncio.to_thread(self.add_thumbnails, clips)
+        return clips
+
+    def add_thumbnails(self, clips: List[Dict]) -> None:
+        """Extract local thumbnails for each clip whose original file is available, cached under temp/thumbnails."""
+        for clip in clips:
+            source_path = self.find_original_path(clip["video_id"])
+            if source_path and os.path.exists(source_path):
+                clip["thumbnails"] = clip_thumbnails(clip, source_path, self.thumbnails_dir)
+
+    def clamp_to_videos(self, edit_plan: Dict, default_video_id: Optional[str] = None) -> None:
+        """Pull trims that run past the end of their video back inside it, logging each change."""
+        video_ids = {action.get("video_id") for action in edit_plan.get("actions", []) if action.get("type") == "trim"}
+        video_ids.add(default_video_id)
+        durations = {
+            video_id: details["duration"]
+            for video_id, details in self.video_details([video_id for video_id in video_ids if video_id]).items()
+        }
+        for change in clamp_plan(edit_plan, durations, default_video_id):
+            logger.warning(change)
+
     def get_video_metadata(self, video_id: str) -> Optional[Dict]:
-        """Retrieve video metadata from MongoDB."""
+        """Retrieve video metadata from the catalog."""
         try:
-            metadata = self.metadata_collection.find_one({"video_id": video_id})
+            metadata = self.catalog.get(video_id)
             if metadata:
-                print(f"Found metadata for video ID {video_id}")  # Debug log
+                logger.debug(f"Found metadata for video ID {video_id}")
             else:
-                print(f"No metadata found for video ID {video_id}")  # Debug log
+                logger.debug(f"No metadata found for video ID {video_id}")
             return metadata
         except Exception as e:
-            print(f"Error retrieving metadata from MongoDB: {str(e)}")
+            logger.error(f"Error retrieving metadata from the catalog: {str(e)}")
+            return None
+
+    def save_job(self, path: str, new_job: bool = False) -> None:
+        """Persist the state of an upload job so it can be resumed if the CLI exits."""
+        metadata = self.video_metadata[path]
+        now = datetime.utcnow()
+        job = {
+            "path": path,
+            "task_id": metadata.task_id,
+            "status": metadata.status.value,
+            "error": metadata.error,
+            "video_id": metadata.video_id,
+            "fingerprint": metadata.fingerprint,
+            "proxy_path": metadata.proxy_path,
+            "chunk_tasks": metadata.chunk_tasks,
+            # Lets `resume` in another process tell a running upload from an abandoned one
+            "owner_pid": os.getpid(),
+            "owner_host": socket.gethostname(),
+            "updated_at": now,
+        }
+        if new_job:
+            job["created_at"] = now
+        try:
+            self.jobs.upsert(path, job)
+        except Exception as e:
+            logger.warning(f"Could not save upload job for {path}: {str(e)}")
+
+    def list_pending_jobs(self) -> List[Dict]:
+        """List upload jobs that had not finished when the CLI last exited."""
+        pending = [
+            status.value
+            for status in (
+                VideoStatus.PENDING, VideoStatus.CONFORMING, VideoStatus.PROXYING,
+                VideoStatus.SPLITTING, VideoStatus.UPLOADING, VideoStatus.INDEXING, VideoStatus.TIMED_OUT,
+            )
+        ]
+        return [job for job in self.jobs.list() if job.get("status") in pending]
+
+    def set_upload_status(self, path: str, status: VideoStatus, error: Optional[str] = None) -> None:
+        """Update a file's upload state, persist it and report it to the progress display."""
+        metadata = self.video_metadata[path]
+        metadata.status = status
+        if error:
+            metadata.error = error
+        self.save_job(path)
+        if self.upload_progress:
+            self.upload_progress.set_status(path, status.value, error)
+
+    async def call_with_retries(self, func, *args):
+        """
+        Run a synchronous SDK call in a worker thread, retrying transient failures.
+
+        Waits retry_backoff seconds before the first retry and doubles the
+        wait on each further attempt.
+        """
+        upload_config = get_config()["upload"]
+        delay = upload_config["retry_backoff"]
+        for attempt in range(upload_config["retries"] + 1):
+            try:
+                return await asyncio.to_thread(func, *args)
+            except Exception as e:
+                if attempt >= upload_config["retries"] or not is_transient_error(e):
+                    raise
+                logger.warning(f"{func.__name__} failed ({str(e)}), retrying in {delay:.0f}s")
+                await asyncio.sleep(delay)
+                delay *= 2
+
+    async def run_upload_jobs(self, paths: List[str], worker) -> List[Dict]:
+        """
+        Run an upload coroutine for each file with bounded parallelism and a per-file timeout.
+
+        Returns:
+            List[Dict]: One upload summary per file, in input order
+        """
+        upload_config = get_config()["upload"]
+        semaphore = asyncio.Semaphore(max(upload_config["max_concurrent"], 1))
+        timeout = upload_config["timeout"] or None
+        started = time.perf_counter()
+
+        async def bounded(path: str) -> None:
+            async with semaphore:
+                file_timeout = timeout * await self.expected_chunks(path) if timeout else None
+                try:
+                    await asyncio.wait_for(worker(path), file_timeout)
+                except asyncio.TimeoutError:
+                    if self.video_metadata[path].resumable:
+                        # Indexing carries on remotely, so keep the job for `resume` to pick up
+                        self.set_upload_status(
+                            path, VideoStatus.TIMED_OUT,
+                            f"Timed out after {file_timeout}s while indexing; run resume to finish it",
+                        )
+                    else:
+                        self.set_upload_status(path, VideoStatus.ERROR, f"Timed out after {file_timeout}s")
+                    logger.error(f"Upload of {path} timed out after {file_timeout}s")
+                finally:
+                    self.upload_probes.pop(path, None)
+
+        self.upload_progress = UploadProgress(paths)
+        try:
+            await asyncio.gather(*[bounded(path) for path in paths])
+        finally:
+            self.upload_progress.close()
+            self.upload_progress = None
+
+        summaries = [upload_summary(self.video_metadata[path]) for path in paths if path in self.video_metadata]
+        log_upload_summary(summaries, time.perf_counter() - started)
+        return summaries
+
+    async def expected_chunks(self, path: str) -> int:
+        """
+        How many chunks a file will be indexed as, so long files get a proportionally longer timeout.
+
+        The probe is kept in upload_probes so the upload doesn't probe the file again.
+        """
+        if not get_config()["chunking"]["enabled"] or not os.path.exists(path):
+            return 1
+        try:
+            info = await asyncio.to_thread(probe_video, path)
+        except ProbeError:
+            return 1
+        self.upload_probes[path] = info
+        if not needs_chunking(info):
+            return 1
+        chunking = get_config()["chunking"]
+        return len(plan_chunks(info["duration"], chunking["chunk_duration"], chunking["overlap"]))
+
+    async def upload_videos(self, paths: List[str]) -> List[Dict]:
+        """Upload and index several videos concurrently, showing per-file progress."""
+        return await self.run_upload_jobs(paths, self.upload_video_async)
+
+    async def validate_upload(self, path: str) -> Optional[List[str]]:
+        """
+        Probe a file and check it against the validation rules before uploading.
+
+        Returns:
+            Optional[List[str]]: Problems that conforming the file or indexing
+            a proxy will fix (empty if it can be uploaded as-is), or None if
+            the file was rejected
+        """
+        validation_config = get_config()["validation"]
+        if not validation_config["enabled"]:
+            return []
+        try:
+            info = self.video_metadata[path].probe or await asyncio.to_thread(probe_video, path)
+        except ProbeError as e:
+            fatal, fixable = [str(e)], []
+        else:
+            self.video_metadata[path].probe = info
+            fatal, fixable = check_video(info)
+
+        # A proxy is H.264/AAC and smaller, so it fixes anything conforming would
+        if fixable and not validation_config["conform"] and not get_config()["proxy"]["enabled"]:
+            fatal.append("set validation.conform to transcode it to a supported format")
+        if fatal:
+            reason = f"Rejected: {'; '.join(fixable + fatal)}"
+            self.set_upload_status(path, VideoStatus.ERROR, reason)
+            logger.error(f"{path}: {reason}")
             return None
+        return fixable
+
+    async def check_proxy(self, proxy_path: str) -> None:
+        """Check a freshly made proxy against the validation rules, raising ValueError if it still fails them."""
+        if not get_config()["validation"]["enabled"]:
+            return
+        fatal, fixable = check_video(await asyncio.to_thread(probe_video, proxy_path))
+        if fatal or fixable:
+            os.remove(proxy_path)
+            raise ValueError(f"Proxy {proxy_path} can't be indexed: {'; '.join(fixable + fatal)}")
 
     async def upload_video_async(self, path: str) -> None:
         """Asynchronously upload and index a video."""
+        shots_task = None
         try:
-            print(f"\nUploading video: {path}")
-            self.video_metadata[path] = VideoMetadata(path=path, task_id=None, status=VideoStatus.UPLOADING)
-            task_id = await upload_video(path)
+            logger.debug(f"Uploading video: {path}")
+            self.video_metadata[path] = VideoMetadata(
+                path=path, task_id=None, status=VideoStatus.PENDING, probe=self.upload_probes.get(path)
+            )
+            self.save_job(path, new_job=True)
+
+            # Reject files that can't be indexed before spending time hashing and uploading them
+            problems = await self.validate_upload(path)
+            if problems is None:
+                return
+
+            # Skip the upload if identical content is already indexed
+            fingerprint = await asyncio.to_thread(fingerprint_file, path)
+            self.video_metadata[path].fingerprint = fingerprint
+            existing = self.find_by_fingerprint(fingerprint)
+            if existing:
+                self.video_metadata[path].video_id = existing["video_id"]
+                self.video_metadata[path].duplicate = True
+                self.video_id_to_path.setdefault(existing["video_id"], existing["original_path"])
+                logger.info(
+                    f"{path} is identical to {existing['original_path']}, "
+                    f"reusing video ID {existing['video_id']}"
+                )
+                self.set_upload_status(path, VideoStatus.READY)
+                return
+
+            # Shot detection runs locally while the video uploads and indexes
+            shots_task = asyncio.create_task(asyncio.to_thread(detect_shots, path))
+
+            probe = self.video_metadata[path].probe
+            if probe is None and get_config()["chunking"]["enabled"]:
+                # Validation probes every file; without it, the duration is still needed to split long files
+                try:
+                    probe = self.video_metadata[path].probe = await asyncio.to_thread(probe_video, path)
+                except ProbeError as e:
+                    logger.warning(f"Could not probe {path}, uploading it whole: {str(e)}")
+            if probe and needs_chunking(probe):
+                await self.upload_chunks(path, shots_task)
+                return
+
+            upload_path = path
+            if get_config()["proxy"]["enabled"]:
+                # The proxy is H.264/AAC and smaller, so it also fixes anything conforming would
+                self.set_upload_status(path, VideoStatus.PROXYING)
+                upload_path = os.path.abspath(await asyncio.to_thread(make_proxy, path, self.temp_dir))
+                await self.check_proxy(upload_path)
+                self.video_metadata[path].proxy_path = upload_path
+            elif problems:
+                logger.info(f"Conforming {path}: {'; '.join(problems)}")
+                self.set_upload_status(path, VideoStatus.CONFORMING)
+                upload_path = await asyncio.to_thread(conform_video, path, self.temp_dir)
+
+            self.set_upload_status(path, VideoStatus.UPLOADING)
+            try:
+                # The SDK client is synchronous, so the upload runs in a worker thread
+                task_id = await self.call_with_retries(create_upload_task, upload_path, self.index_id)
+            finally:
+                # Renders cut from the original, so the mezzanine is only needed for the upload
+                is_mezzanine = upload_path not in (path, self.video_metadata[path].proxy_path)
+                if is_mezzanine and os.path.exists(upload_path):
+                    os.remove(upload_path)
             self.video_metadata[path].task_id = task_id
-            self.video_metadata[path].status = VideoStatus.INDEXING
-            
-            # Wait for indexing to complete
-            while True:
-                status = await client.task.get(task_id)
-                if status.status == "completed":
-                    self.video_metadata[path].status = VideoStatus.READY
-                    # Store the mapping of video_id to original path
-                    self.video_id_to_path[status.video_id] = path
-                    # Save metadata to MongoDB
-                    print(f"\nVideo indexing completed. Saving metadata...")  # Debug log
-                    self.save_video_metadata(status.video_id, path)
-                    print(f"Video uploaded successfully. ID: {status.video_id} -> Path: {path}")
-                    break
-                elif status.status == "failed":
-                    self.video_metadata[path].status = VideoStatus.ERROR
-                    self.video_metadata[path].error = status.error
-                    break
-                await asyncio.sleep(5)
+            self.set_upload_status(path, VideoStatus.INDEXING)
+
+            await self.wait_for_indexing(path, shots_task)
         except Exception as e:
-            self.video_metadata[path].status = VideoStatus.ERROR
-            self.video_metadata[path].error = str(e)
-            print(f"Error during upload: {str(e)}")  # Debug log
+            self.set_upload_status(path, VideoStatus.ERROR, str(e))
+            logger.error(f"Error during upload of {path}: {str(e)}")
+        finally:
+            if shots_task and not shots_task.done():
+                shots_task.cancel()
+
+    async def wait_for_indexing(self, path: str, shots_task: Optional[asyncio.Task] = None) -> None:
+        """
+        Poll a video's indexing task until it finishes, then save its metadata.
+
+        Args:
+            path (str): The file being indexed
+            shots_task (asyncio.Task, optional): Shot detection already started
+                for the file; detection runs after indexing if none is given
+        """
+        metadata = self.video_metadata[path]
+        task = await self.poll_task(metadata.task_id, lambda status: self.set_upload_status(path, status))
+        if task.status != "ready":
+            self.set_upload_status(path, VideoStatus.ERROR, f"Indexing failed with status {task.status}")
+            return
+
+        metadata.video_id = task.video_id
+        # Store the mapping of video_id to original path
+        self.video_id_to_path[task.video_id] = path
+        # Save metadata to the catalog
+        logger.debug("Video indexing completed. Saving metadata...")
+        shots = await (shots_task or asyncio.to_thread(detect_shots, path))
+        self.save_video_metadata(task.video_id, path, metadata.fingerprint, metadata.probe, shots, metadata.proxy_path)
+        self.set_upload_status(path, VideoStatus.READY)
+        logger.info(f"Video uploaded successfully. ID: {task.video_id} -> Path: {path}")
+
+    async def poll_task(self, task_id: str, on_status=None):
+        """Poll an indexing task until it is ready or has failed, returning the finished task."""
+        while True:
+            task = await self.call_with_retries(get_task, task_id)
+            status = TASK_STATUSES.get(task.status, VideoStatus.INDEXING)
+            if status in (VideoStatus.READY, VideoStatus.ERROR):
+                return task
+            if on_status:
+                on_status(status)
+            await asyncio.sleep(get_config()["upload"]["poll_interval"])
+
+    async def upload_chunks(self, path: str, shots_task: asyncio.Task) -> None:
+        """
+        Index a file that is too long for the indexer as overlapping chunks.
+
+        Each chunk is transcoded and uploaded in turn, then all of them are
+        indexed in parallel. The chunk task ids are saved in the upload job as
+        they are created, so `resume` can finish indexing if the CLI exits.
+        """
+        metadata = self.video_metadata[path]
+        chunking = get_config()["chunking"]
+        chunks = plan_chunks(metadata.probe["duration"], chunking["chunk_duration"], chunking["overlap"])
+        logger.info(f"{path} is {format_duration(metadata.probe['duration'])} long, indexing it as {len(chunks)} chunks")
+        metadata.chunk_tasks = [{"offset": start, "duration": length, "task_id": None} for start, length in chunks]
+
+        for i, chunk in enumerate(metadata.chunk_tasks):
+            start, length = chunk["offset"], chunk["duration"]
+            logger.debug(f"Chunk {i + 1} of {len(chunks)}: {format_duration(start)} for {format_duration(length)}")
+            self.set_upload_status(path, VideoStatus.SPLITTING)
+            chunk_path = await asyncio.to_thread(make_chunk, path, self.temp_dir, i, start, length)
+            self.set_upload_status(path, VideoStatus.UPLOADING)
+            try:
+                chunk["task_id"] = await self.call_with_retries(create_upload_task, chunk_path, self.index_id)
+            finally:
+                os.remove(chunk_path)
+            self.save_job(path)
+
+        self.set_upload_status(path, VideoStatus.INDEXING)
+        await self.wait_for_chunks(path, shots_task)
+
+    async def wait_for_chunks(self, path: str, shots_task: Optional[asyncio.Task] = None) -> None:
+        """
+        Poll the indexing tasks of a chunked file until they all finish, then save its metadata.
+
+        Every chunk gets a catalog entry with the ID of its parent and its
+        offset into the original; the parent entry holds the file's
+        fingerprint, technical metadata and shots and is what search results
+        are translated back to.
+        """
+        metadata = self.video_metadata[path]
+        chunks = [(chunk["offset"], chunk["duration"]) for chunk in metadata.chunk_tasks]
+        tasks = await asyncio.gather(*[self.poll_task(chunk["task_id"]) for chunk in metadata.chunk_tasks])
+        failed = [i + 1 for i, task in enumerate(tasks) if task.status != "ready"]
+        if failed:
+            self.set_upload_status(
+                path, VideoStatus.ERROR, f"Indexing failed for chunk(s) {', '.join(map(str, failed))}"
+            )
+            return
+
+        parent_id = parent_video_id(metadata.fingerprint["content_hash"])
+        now = datetime.utcnow()
+        children = []
+        for i, (task, (start, length)) in enumerate(zip(tasks, chunks)):
+            child = {"video_id": task.video_id, "offset": start, "duration": length}
+            children.append(child)
+            self.catalog.upsert(
+                task.video_id, dict(child, parent_id=parent_id, chunk_index=i, original_path=path, uploaded_at=now)
+            )
+        shots = await (shots_task or asyncio.to_thread(detect_shots, path))
+        self.save_video_metadata(parent_id, path, metadata.fingerprint, metadata.probe, shots)
+        self.catalog.upsert(parent_id, {"chunks": children})
+
+        metadata.video_id = parent_id
+        self.video_id_to_path[parent_id] = path
+        self.set_upload_status(path, VideoStatus.READY)
+        logger.info(f"Video uploaded successfully as {len(chunks)} chunks. ID: {parent_id} -> Path: {path}")
+
+    async def resume_uploads(self) -> List[Dict]:
+        """
+        Finish upload jobs interrupted by a previous run.
+
+        Jobs whose indexing tasks were all created, including every chunk of a
+        chunked file, are polled until they finish and their metadata is saved.
+        Jobs that were interrupted before that can't be recovered and are
+        marked as failed so the file can be uploaded again. Jobs another
+        process is still working on (e.g. an upload in another shell) are left
+        alone.
+
+        Returns:
+            List[Dict]: The jobs that were resumed or marked as failed
+        """
+        jobs = []
+        for job in self.list_pending_jobs():
+            if job_in_progress(job):
+                logger.info(
+                    f"Skipping {job['path']}: still being uploaded by process {job.get('owner_pid')} "
+                    f"on {job.get('owner_host') or 'another machine'}"
+                )
+            else:
+                jobs.append(job)
+        resumable = []
+        for job in jobs:
+            path = job["path"]
+            self.video_metadata[path] = VideoMetadata(
+                path=path,
+                task_id=job.get("task_id"),
+                status=VideoStatus(job["status"]),
+                video_id=job.get("video_id"),
+                fingerprint=job.get("fingerprint"),
+                proxy_path=job.get("proxy_path"),
+                chunk_tasks=job.get("chunk_tasks"),
+            )
+            if self.video_metadata[path].resumable:
+                resumable.append(path)
+            else:
+                self.set_upload_status(
+                    path, VideoStatus.ERROR, "Interrupted before the upload finished; upload the file again"
+                )
+
+        async def resume(path: str) -> None:
+            try:
+                if self.video_metadata[path].chunk_tasks:
+                    await self.wait_for_chunks(path)
+                else:
+                    await self.wait_for_indexing(path)
+            except Exception as e:
+                self.set_upload_status(path, VideoStatus.ERROR, str(e))
+                logger.error(f"Error resuming upload of {path}: {str(e)}")
+
+        if resumable:
+            await self.run_upload_jobs(resumable, resume)
+        return [upload_summary(self.video_metadata[job["path"]]) for job in jobs]
 
     def analyze_prompt(self, prompt: str) -> Dict:
         """First Gemini call to analyze prompt and extract structured information."""
@@ -160,7 +777,7 @@ class VideoEditor:
         """
 
         response = gemini_client.models.generate_content(
-            model="gemini-2.0-flash",
+            model=get_config()["models"]["analysis"],
             contents=[analysis_prompt],
         )
         try:
@@ -174,38 +791,82 @@ class VideoEditor:
             }
 
     async def process_edit(
-        self, prompt: str, video_paths: List[str], skip_upload: bool = False
-    ):
-        """Main function to process the video edit request."""
+        self,
+        prompt: str,
+        video_paths: List[str],
+        skip_upload: bool = False,
+        output_path: Optional[str] = None,
+        assume_yes: bool = False,
+        plan_path: Optional[str] = None,
+        dry_run: bool = False,
+        session: Optional[EditSession] = None,
+    ) -> Dict:
+        """
+        Main function to process the video edit request.
+
+        When a session is given, the request is treated as a follow-up: the
+        session's clips and previous plan are passed to the planner so it
+        revises the existing edit instead of starting from scratch.
+
+        Returns:
+            Dict: The outcome of the edit: status, analysis, clips, plan,
+            output path, per-stage timings and any errors
+        """
+        result = {
+            "status": "failed",
+            "prompt": prompt,
+            "uploads": [],
+            "analysis": None,
+            "clips": [],
+            "plan": None,
+            "output_path": None,
+            "input_path": None,
+            "sources": {},
+            "dry_run": None,
+            "timings": {},
+            "errors": [],
+        }
+        timings = result["timings"]
+
+        def fail(message: str, status: str = "failed") -> Dict:
+            logger.error(message.strip())
+            result["status"] = status
+            result["errors"].append(message.strip())
+            return self.record_edit(result, session)
+
         if not skip_upload:
             # 1. Upload videos asynchronously
-            print("Uploading videos...")
-            upload_tasks = [self.upload_video_async(path) for path in video_paths]
-            await asyncio.gather(*upload_tasks)
-            
-            # Check for any upload errors
-            for path, metadata in self.video_metadata.items():
-                if metadata.status == VideoStatus.ERROR:
-                    print(f"Error uploading {path}: {metadata.error}")
+            logger.info("Uploading videos...")
+            with timed(timings, "upload"):
+                result["uploads"] = await self.upload_videos(video_paths)
+
+            # Upload errors are already logged in the upload summary
+            for upload in result["uploads"]:
+                if upload["status"] in FAILED_STATUSES:
+                    result["errors"].append(f"Error uploading {upload['path']}: {upload['error']}")
 
         # 2. Analyze prompt
-        print("\nAnalyzing prompt...")
-        analysis = self.analyze_prompt(prompt)
-        print(f"Search queries: {analysis['search_queries']}")
-        print(f"Editing actions: {analysis['editing_actions']}")
-        print(f"Target videos: {analysis['target_videos']}")
+        logger.info("Analyzing prompt...")
+        with timed(timings, "analyze"):
+            analysis = self.analyze_prompt(prompt)
+        result["analysis"] = analysis
+        logger.info(f"Search queries: {analysis['search_queries']}")
+        logger.debug(f"Editing actions: {analysis['editing_actions']}")
+        logger.debug(f"Target videos: {analysis['target_videos']}")
 
         # 3. Search for clips using Twelvelabs
-        print("\nSearching for relevant clips...")
+        logger.info("Searching for relevant clips...")
         clips = []
-        for query in analysis["search_queries"]:
-            # Run search_video in a thread pool since it's synchronous
-            search_results = await asyncio.to_thread(search_video, query)
-            clips.extend(search_results)
+        with timed(timings, "search"):
+            for query in analysis["search_queries"]:
+                clips.extend(await self.search_clips(query, thumbnails=not dry_run))
+
+        if session:
+            clips = merge_clips(session.clips, clips)
+        result["clips"] = clips
 
         if not clips:
-            print("No relevant clips found.")
-            return
+            return fail("No relevant clips found.", status="no_clips")
 
         # Check if all clips are from videos we have in our database
         missing_videos = []
@@ -214,85 +875,378 @@ class VideoEditor:
                 missing_videos.append(clip['video_id'])
 
         if missing_videos:
-            print("\nWarning: Some videos referenced in the search results have not been uploaded:")
+            logger.warning("Some videos referenced in the search results have not been uploaded:")
             for video_id in missing_videos:
-                print(f"- Video ID: {video_id}")
-            print("\nPlease upload these videos first using option 1.")
-            return
+                logger.warning(f"- Video ID: {video_id}")
+            return fail("Please upload these videos first using option 1.")
 
-        print("\nFound clips:")
+        logger.info(f"Found {len(clips)} clip(s)")
         for i, clip in enumerate(clips):
-            print(f"\nClip {i+1}:")
-            print(f"Video ID: {clip['video_id']}")
-            print(f"Time range: {clip['start_time']} - {clip['end_time']}")
-            print(f"Score: {clip['score']}")
-            print(f"Thumbnail URL: {clip['thumbnail_url']}")
+            logger.debug(
+                f"Clip {i+1}: video {clip['video_id']}, {clip['start_time']} - {clip['end_time']}, "
+                f"score {clip['score']}, thumbnail {clip['thumbnail_url']}"
+            )
 
         # 4. Generate edit plan using the prompt generator
-        print("\nGenerating edit plan...")
+        logger.info("Generating edit plan...")
         try:
-            edit_plan_json = generate_prompt(prompt, clips)
+            with timed(timings, "plan"):
+                videos = self.video_details(list(dict.fromkeys(clip["video_id"] for clip in clips)))
+                if session and session.plan:
+                    edit_plan_json = generate_prompt(
+                        prompt, clips, previous_plan=session.plan, history=session.context(), videos=videos
+                    )
+                else:
+                    edit_plan_json = generate_prompt(prompt, clips, videos=videos)
             edit_plan = json.loads(edit_plan_json)
-            print("\nGenerated edit plan:")
-            print(json.dumps(edit_plan, indent=2))
+            self.clamp_to_videos(edit_plan, clips[0]["video_id"])
+            logger.debug(f"Generated edit plan:\n{json.dumps(edit_plan, indent=2)}")
         except Exception as e:
-            print(f"Error generating edit plan: {str(e)}")
-            return
+            return fail(f"Error generating edit plan: {str(e)}")
 
-        # 5. Ask user if they want to proceed with the edit
-        print("\nWould you like to proceed with the edit?")
-        print("1. Yes, generate and execute FFmpeg command")
-        print("2. No, exit")
-        
-        choice = input("\nSelect an option (1-2): ").strip()
-        
-        if choice == "1":
+        # 5. Review the plan clip by clip before rendering
+        if assume_yes or dry_run:
+            reviewed_plan = edit_plan
+        else:
+            reviewed_plan = review_plan(edit_plan, clips)
+
+        if plan_path:
+            save_edit_plan(reviewed_plan or edit_plan, plan_path, source=clips[0]['video_id'])
+            logger.info(f"Edit plan saved to: {plan_path}")
+
+        result["plan"] = reviewed_plan or edit_plan
+        if reviewed_plan is None:
+            logger.info("Exiting without generating edit.")
+            result["status"] = "cancelled"
+            return self.record_edit(result, session)
+        edit_plan = reviewed_plan
+        # Nudges made during review can also run past the end of a video
+        self.clamp_to_videos(edit_plan, clips[0]["video_id"])
+
+        try:
+            # Try to get the original file path from our mapping or the catalog
+            video_id = clips[0]['video_id']
+            logger.debug(f"Looking up original file for video ID: {video_id}")
+            input_path = self.find_original_path(video_id)
+            if not input_path:
+                logger.error(f"Original file not found for video ID: {video_id}")
+                return fail("Please upload the video first using option 1.")
+
+            logger.debug(f"Found original file: {input_path}")
+
+            if not os.path.exists(input_path):
+                return fail(f"Error: Original file not found at {input_path}")
+
+            sources = self.collect_sources(edit_plan)
+            result["input_path"] = input_path
+            result["sources"] = sources
+
+            if dry_run:
+                description = describe_plan(edit_plan, input_path, output_path or self.default_output_path(), sources)
+                print_dry_run(description)
+                result["dry_run"] = description
+                result["status"] = "planned"
+            else:
+                with timed(timings, "render"):
+                    final_path = self.render_plan(edit_plan, input_path, output_path, sources)
+                logger.info(f"Edit completed successfully! Output saved to: {final_path}")
+                result["output_path"] = final_path
+                result["status"] = "rendered"
+
+        except Exception as e:
+            logger.debug("Full error details:", exc_info=True)
+            return fail(f"Error during FFmpeg execution: {str(e)}")
+
+        if session:
+            session.record_turn(prompt, clips, edit_plan, result["output_path"])
+            session.save()
+
+        return self.record_edit(result, session)
+
+    def record_edit(
+        self, result: Dict, session: Optional[EditSession] = None, replay_of: Optional[str] = None
+    ) -> Dict:
+        """Save an edit run to the history and return its result with the new edit_id."""
+        edit_id = new_edit_id()
+        record = {
+            key: result.get(key)
+            for key in (
+                "status", "prompt", "analysis", "clips", "plan", "output_path", "input_path", "sources",
+                "timings", "errors",
+            )
+        }
+        record.update(
+            edit_id=edit_id,
+            created_at=datetime.now().isoformat(),
+            session_id=session.session_id if session else None,
+            replay_of=replay_of,
+        )
+        try:
+            self.history.upsert(edit_id, record)
+            result["edit_id"] = edit_id
+            logger.debug(f"Recorded edit {edit_id} in the history")
+        except Exception as e:
+            # The edit itself succeeded or failed on its own terms; losing its history entry shouldn't change that
+            logger.warning(f"Could not save the edit to the history: {str(e)}")
+        return result
+
+    def list_edits(self) -> List[Dict]:
+        """Every recorded edit, most recent first."""
+        return sorted(self.history.list(), key=lambda edit: edit.get("created_at", ""), reverse=True)
+
+    def replay_edit(self, edit_id: str, output_path: Optional[str] = None, dry_run: bool = False) -> Dict:
+        """
+        Render the plan of a recorded edit again, without analyzing or planning anything.
+
+        Sources that have moved since are looked up in the catalog. The replay
+        is recorded in the history too, pointing back at the original edit.
+
+        Returns:
+            Dict: The outcome, in the same shape as process_edit's
+        """
+        edit = self.history.get(edit_id)
+        if not edit:
+            raise KeyError(f"No edit {edit_id} in the history")
+        if not edit.get("plan"):
+            raise ValueError(f"Edit {edit_id} has no plan to replay ({edit['status']})")
+        result = {
+            "status": "failed",
+            "prompt": edit.get("prompt"),
+            "analysis": edit.get("analysis"),
+            "clips": edit.get("clips") or [],
+            "plan": edit["plan"],
+            "output_path": None,
+            "input_path": None,
+            "sources": {},
+            "dry_run": None,
+            "timings": {},
+            "errors": [],
+        }
+
+        input_path = edit.get("input_path")
+        if (not input_path or not os.path.exists(input_path)) and result["clips"]:
+            input_path = self.find_original_path(result["clips"][0]["video_id"])
+        if not input_path or not os.path.exists(input_path):
+            raise FileNotFoundError(f"Original file not found at {edit.get('input_path')}")
+        sources = {}
+        for video_id, path in (edit.get("sources") or {}).items():
+            if not os.path.exists(path):
+                path = self.find_original_path(video_id)
+            if not path or not os.path.exists(path):
+                raise FileNotFoundError(f"Original file not found for video ID: {video_id}")
+            sources[video_id] = path
+        result["input_path"] = input_path
+        result["sources"] = sources
+
+        if dry_run:
+            description = describe_plan(edit["plan"], input_path, output_path or self.default_output_path(), sources)
+            print_dry_run(description)
+            result["dry_run"] = description
+            result["status"] = "planned"
+            return self.record_edit(result, replay_of=edit_id)
+
+        try:
+            with timed(result["timings"], "render"):
+                result["output_path"] = self.render_plan(edit["plan"], input_path, output_path, sources)
+            result["status"] = "rendered"
+            logger.info(f"Replay completed successfully! Output saved to: {result['output_path']}")
+        except Exception as e:
+            logger.debug("Full error details:", exc_info=True)
+            logger.error(f"Error during FFmpeg execution: {str(e)}")
+            result["errors"].append(f"Error during FFmpeg execution: {str(e)}")
+        return self.record_edit(result, replay_of=edit_id)
+
+    def find_original_path(self, video_id: str) -> Optional[str]:
+        """Look up the original file path for a video ID, in memory first and then in the catalog."""
+        if video_id in self.video_id_to_path:
+            return self.video_id_to_path[video_id]
+
+        metadata = self.get_video_metadata(video_id)
+        if not metadata:
+            return None
+        # Update in-memory mapping
+        self.video_id_to_path[video_id] = metadata['original_path']
+        return metadata['original_path']
+
+    def resolve_source(self, source: str) -> Optional[str]:
+        """Resolve a render source given either as a local file path or as a video ID."""
+        if os.path.exists(source):
+            return os.path.abspath(source)
+        return self.find_original_path(source)
+
+    def collect_sources(self, edit_plan: Dict) -> Dict[str, str]:
+        """Map the video_id of every trim that names one to its original file path."""
+        sources = {}
+        for action in edit_plan.get("actions", []):
+            video_id = action.get("video_id")
+            if action["type"] != "trim" or not video_id or video_id in sources:
+                continue
+            path = self.find_original_path(video_id)
+            if not path or not os.path.exists(path):
+                raise FileNotFoundError(f"Original file not found for video ID: {video_id}")
+            sources[video_id] = path
+        return sources
+
+    def default_output_path(self) -> str:
+        """Build a timestamped output path in the output directory."""
+        return str(self.output_dir / f"edited_{datetime.now().strftime('%Y%m%d_%H%M%S')}.mp4")
+
+    def render_plan(
+        self,
+        edit_plan: Dict,
+        input_path: str,
+        output_path: Optional[str] = None,
+        sources: Optional[Dict[str, str]] = None,
+    ) -> str:
+        """Render an edit plan against a source video and return the output path."""
+        if not output_path:
+            output_path = self.default_output_path()
+        else:
+            Path(output_path).parent.mkdir(parents=True, exist_ok=True)
+
+        logger.info("Generating FFmpeg command and executing...")
+        logger.info(f"Input: {input_path}")
+        logger.info(f"Output: {output_path}")
+
+        # Generate and execute FFmpeg command
+        progress = RenderProgress(plan_render_seconds(edit_plan, sources))
+        try:
+            return generate_ffmpeg_from_plan(edit_plan, input_path, output_path, sources, on_progress=progress)
+        finally:
+            progress.close()
+
+    def reconcile(self, search_dirs: Optional[List[str]] = None, fix: bool = False, assume_yes: bool = False) -> List[Dict]:
+        """
+        Compare the Twelvelabs index, the catalog and the filesystem, optionally fixing mismatches.
+
+        Args:
+            search_dirs (List[str], optional): Folders searched (recursively) for
+                moved or unknown files (default: the watched folders and the
+                folders of every file in the catalog)
+            fix (bool): Apply the fix for each mismatch after confirmation
+            assume_yes (bool): Apply fixes without asking
+
+        Returns:
+            List[Dict]: The mismatches found, each marked fixed or not when fix is set
+        """
+        catalog = self.list_uploaded_videos()
+        index_videos = list_index_videos(self.index_id)
+        if not search_dirs:
+            search_dirs = list(get_config()["watch"]["folders"])
+            folders = {os.path.dirname(entry["original_path"]) for entry in catalog if entry.get("original_path")}
+            search_dirs += sorted(folder for folder in folders if os.path.isdir(folder))
+        logger.debug(f"Looking for moved files in: {', '.join(search_dirs)}")
+        finder = FileFinder(search_dirs, get_config()["upload"]["extensions"])
+        pending, unresolved = self.pending_upload_videos()
+        issues = find_mismatches(index_videos, catalog, finder, pending, unresolved)
+
+        fixes = [issue for issue in issues if issue["fix"]]
+        if not fix or not fixes:
+            return issues
+        if not assume_yes:
+            print_mismatches(issues, fix=False)
+        if not confirm_action(f"\nApply {len(fixes)} fix(es)? This can delete videos from the index.", assume_yes):
+            for issue in fixes:
+                issue["fixed"], issue["error"] = False, "cancelled"
+            return issues
+
+        for issue in fixes:
             try:
-                # Get the video path from the first clip
-                if not clips:
-                    print("No clips found to edit.")
-                    return
-                
-                # Try to get the original file path from our mapping or MongoDB
-                video_id = clips[0]['video_id']
-                print(f"\nLooking up original file for video ID: {video_id}")
-                
-                # First try in-memory mapping
-                if video_id in self.video_id_to_path:
-                    input_path = self.video_id_to_path[video_id]
+                self.apply_fix(issue)
+                issue["fixed"] = True
+            except Exception as e:
+                logger.error(f"Could not fix {issue['video_id']}: {str(e)}")
+                issue["fixed"], issue["error"] = False, str(e)
+        return issues
+
+    def pending_upload_videos(self) -> Tuple[Dict[str, str], bool]:
+        """
+        Find the indexed videos that belong to unfinished upload jobs.
+
+        Chunks and timed-out uploads are in the index before they are in the
+        catalog, so reconcile must not mistake them for strays.
+
+        Returns:
+            Tuple[Dict[str, str], bool]: Video IDs mapped to the file being
+            uploaded, and whether any job's task could not be looked up
+        """
+        pending, unresolved = {}, False
+        for job in self.list_pending_jobs():
+            task_ids = [job.get("task_id")] + [chunk["task_id"] for chunk in job.get("chunk_tasks") or []]
+            for task_id in filter(None, task_ids):
+                try:
+                    video_id = get_task(task_id).video_id
+                except Exception as e:
+                    logger.warning(f"Could not look up indexing task {task_id} of {job['path']}: {str(e)}")
+                    video_id = None
+                if video_id:
+                    pending[video_id] = job["path"]
                 else:
-                    # If not in memory, try MongoDB
-                    metadata = self.get_video_metadata(video_id)
-                    if metadata:
-                        input_path = metadata['original_path']
-                        # Update in-memory mapping
-                        self.video_id_to_path[video_id] = input_path
-                    else:
-                        print(f"\nOriginal file not found for video ID: {video_id}")
-                        print("Please upload the video first using option 1.")
-                        return
-                
-                print(f"Found original file: {input_path}")
-                
-                if not os.path.exists(input_path):
-                    print(f"Error: Original file not found at {input_path}")
-                    return
-                
-                output_path = str(self.output_dir / f"edited_{datetime.now().strftime('%Y%m%d_%H%M%S')}.mp4")
-                
-                print(f"\nGenerating FFmpeg command and executing...")
-                print(f"Input: {input_path}")
-                print(f"Output: {output_path}")
-                
-                # Generate and execute FFmpeg command
-                final_path = generate_ffmpeg_from_plan(edit_plan, input_path, output_path)
-                print(f"\nEdit completed successfully! Output saved to: {final_path}")
-                
+                    unresolved = True
+            if job.get("status") in (VideoStatus.PENDING.value, VideoStatus.UPLOADING.value):
+                # A task may be being created right now
+                unresolved = True
+        return pending, unresolved
+
+    def apply_fix(self, issue: Dict) -> None:
+        """Apply the fix reconcile chose for a mismatch."""
+        video_id = issue["video_id"]
+        if issue["fix"] == "relink":
+            for entry in [{"video_id": video_id}, *self.catalog.list(parent_id=video_id)]:
+                self.catalog.upsert(entry["video_id"], {"original_path": issue["new_path"]})
+            self.video_id_to_path[video_id] = issue["new_path"]
+            logger.info(f"Relinked {video_id} to {issue['new_path']}")
+        elif issue["fix"] == "link":
+            self.add_existing_video(video_id, issue["new_path"])
+        elif issue["fix"] in ("delete_index", "delete"):
+            self.remove_video(video_id)
+        elif issue["fix"] == "delete_metadata":
+            self.remove_video(video_id, from_index=False)
+
+    def find_videos(self, target: str) -> List[Dict]:
+        """Find catalog entries by video ID or by original file path."""
+        entry = self.get_video_metadata(target)
+        if entry and entry.get("parent_id"):
+            entry = self.get_video_metadata(entry["parent_id"])
+        if entry:
+            return [entry]
+        path = os.path.abspath(os.path.expanduser(target))
+        # Chunks are removed with their parent, so only whole files are returned
+        return [
+            entry for entry in self.catalog.list(original_path=path)
+            if not entry.get("parent_id")
+        ]
+
+    def remove_video(self, video_id: str, from_index: bool = True) -> None:
+        """
+        Remove a video from the index and everything stored about it locally.
+
+        Deletes the indexed video, its catalog entry (including its technical
+        metadata and shot boundaries), its upload jobs, its cached thumbnails
+        and its proxy. A video that is already gone from the index is not an error.
+        """
+        metadata = self.get_video_metadata(video_id) or {}
+        # A chunked file's own entry is not in the index; its chunks are
+        for chunk in metadata.get("chunks", []):
+            self.remove_video(chunk["video_id"], from_index)
+        if from_index and not metadata.get("chunks"):
+            try:
+                delete_index_video(video_id, self.index_id)
             except Exception as e:
-                print(f"\nError during FFmpeg execution: {str(e)}")
-                print("Full error details:", e)
-        else:
-            print("\nExiting without generating edit.")
+                if error_status_code(e) != 404:
+                    raise
+                logger.info(f"Video {video_id} was already deleted from the index")
+        self.catalog.delete(video_id)
+        for job in self.jobs.list(video_id=video_id):
+            self.jobs.delete(job["path"])
+        self.video_id_to_path.pop(video_id, None)
+        thumbnails = self.thumbnails_dir / video_id
+        if thumbnails.is_dir():
+            shutil.rmtree(thumbnails)
+        proxy = metadata.get("proxy_path")
+        if proxy and os.path.exists(proxy):
+            os.remove(proxy)
+        logger.info(f"Removed video {video_id}")
 
     def cleanup(self, clip_paths: List[str]):
         """Clean up temporary files."""
@@ -308,24 +1262,21 @@ class VideoEditor:
                 os.remove(concat_file)
 
         except Exception as e:
-            print(f"Error during cleanup: {str(e)}")
+            logger.error(f"Error during cleanup: {str(e)}")
 
     def add_existing_video(self, video_id: str, original_path: str) -> None:
-        """Manually add metadata for an already uploaded video."""
-        try:
-            print(f"\nAdding existing video metadata:")
-            print(f"Video ID: {video_id}")
-            print(f"Original Path: {original_path}")
-            
-            # Save to MongoDB
-            self.save_video_metadata(video_id, original_path)
-            
-            # Update in-memory mapping
-            self.video_id_to_path[video_id] = original_path
-            
-            print("Successfully added existing video metadata")
-        except Exception as e:
-            print(f"Error adding existing video: {str(e)}")
+        """Manually add metadata for an already uploaded video; errors are left to the caller to report."""
+        logger.debug(f"Adding existing video metadata: {video_id} -> {original_path}")
+
+        # Save to the catalog
+        self.save_video_metadata(
+            video_id, original_path, fingerprint_file(original_path), shots=detect_shots(original_path)
+        )
+
+        # Update in-memory mapping
+        self.video_id_to_path[video_id] = original_path
+
+        logger.info("Successfully added existing video metadata")
 
 
 def clear_screen():
@@ -343,33 +1294,57 @@ def print_header():
 def get_video_paths() -> List[str]:
     """Get video paths from user input."""
     paths = []
-    print("\nEnter video paths (one per line). Press Enter twice when done:")
-    print("Note: You can paste paths with spaces, no need for quotes")
+    print("\nEnter video paths, folders or glob patterns (one per line). Press Enter twice when done:")
+    print("Note: You can paste paths with spaces, no need for quotes. Use ** in a pattern to search subfolders.")
     while True:
-        path = input("> ").strip()
-        if not path:
+        line = input("> ").strip()
+        if not line:
             break
-        # Remove any surrounding quotes if present
-        path = path.strip("\"'")
-        # Convert to absolute path
-        path = os.path.abspath(path)
-        if os.path.exists(path):
-            paths.append(path)
-        else:
-            print(f"Warning: File not found: {path}")
+        videos, unmatched = expand_video_paths([line])
+        if unmatched:
+            print(f"Warning: No videos found at: {line}")
             print("Please check if the path is correct and try again.")
+            continue
+        if len(videos) > 1:
+            print(f"Added {len(videos)} videos.")
+        paths.extend(video for video in videos if video not in paths)
+
+    if paths and not confirm_uploads(paths):
+        return []
     return paths
 
 
+def confirm_uploads(paths: List[str], assume_yes: bool = False) -> bool:
+    """Show what will be uploaded and ask before starting when running interactively."""
+    print_upload_summary(summarize_uploads(paths))
+    if assume_yes or not sys.stdin.isatty():
+        return True
+    return input("\nStart upload? [y/N]: ").strip().lower() in ("y", "yes")
+
+
+def confirm_action(question: str, assume_yes: bool = False) -> bool:
+    """Ask before a destructive action; without a terminal, only proceed when --yes was given."""
+    if assume_yes:
+        return True
+    if not sys.stdin.isatty():
+        logger.error("Not running interactively; pass --yes to confirm.")
+        return False
+    return input(f"{question} [y/N]: ").strip().lower() in ("y", "yes")
+
+
 def get_edit_prompt() -> str:
     """Get the editing prompt from user input."""
     print("\nEnter your editing prompt:")
     return input("> ").strip()
 
 
-def main_menu():
+def main_menu(project: Optional[Project] = None):
     """Display the main menu and handle user interaction."""
-    editor = VideoEditor()
+    editor = VideoEditor(project)
+    pending_jobs = editor.list_pending_jobs()
+    if pending_jobs:
+        print(f"\n{len(pending_jobs)} upload(s) were interrupted. Run 'python main.py resume' to finish them.")
+        input("\nPress Enter to continue...")
 
     while True:
         clear_screen()
@@ -378,9 +1353,10 @@ def main_menu():
         print("2. Edit Existing Videos")
         print("3. List Uploaded Videos")
         print("4. Add Existing Video Metadata")
-        print("5. Exit")
+        print("5. Start Editing Session")
+        print("6. Exit")
 
-        choice = input("\nSelect an option (1-5): ").strip()
+        choice = input("\nSelect an option (1-6): ").strip()
 
         if choice == "1":
             # Upload and edit flow
@@ -421,11 +1397,7 @@ def main_menu():
             if not uploaded_videos:
                 print("\nNo videos have been uploaded yet.")
             else:
-                print("\nUploaded Videos:")
-                for video in uploaded_videos:
-                    print(f"\nVideo ID: {video['video_id']}")
-                    print(f"Original Path: {video['original_path']}")
-                    print(f"Uploaded At: {video['uploaded_at']}")
+                print_uploaded_videos(uploaded_videos)
             input("\nPress Enter to continue...")
 
         elif choice == "4":
@@ -443,10 +1415,17 @@ def main_menu():
                 input("\nPress Enter to continue...")
                 continue
                 
-            editor.add_existing_video(video_id, original_path)
+            try:
+                editor.add_existing_video(video_id, original_path)
+            except Exception as e:
+                logger.error(f"Error adding existing video: {str(e)}")
             input("\nPress Enter to continue...")
 
         elif choice == "5":
+            # Conversational editing session
+            run_chat(editor, EditSession.create(editor.sessions_dir))
+
+        elif choice == "6":
             print("\nGoodbye!")
             sys.exit(0)
 
@@ -459,5 +1438,634 @@ def main_menu():
         input()
 
 
+def print_uploaded_videos(uploaded_videos: List[Dict]) -> None:
+    """Print the catalog of uploaded videos, flagging entries with identical content."""
+    duplicates = find_duplicates(uploaded_videos)
+    print("\nUploaded Videos:")
+    for video in uploaded_videos:
+        # Chunks are listed as part of their parent
+        if video.get("parent_id"):
+            continue
+        print(f"\nVideo ID: {video['video_id']}")
+        print(f"Original Path: {video['original_path']}")
+        print(f"Uploaded At: {video['uploaded_at']}")
+        technical = video.get("technical")
+        if technical:
+            if technical.get("duration"):
+                print(f"Duration: {format_duration(technical['duration'])}")
+            print(f"Format: {describe_technical(technical)}")
+            if technical.get("creation_time"):
+                print(f"Created: {technical['creation_time']}")
+        if video.get("tags"):
+            print(f"Tags: {', '.join(video['tags'])}")
+        if video.get("proxy_path"):
+            print(f"Indexed Proxy: {video['proxy_path']}")
+        if video.get("chunks"):
+            print(f"Indexed As: {len(video['chunks'])} chunks ({', '.join(chunk['video_id'] for chunk in video['chunks'])})")
+        others = [
+            video_id for video_id in duplicates.get(video.get("content_hash"), [])
+            if video_id != video["video_id"]
+        ]
+        if others:
+            print(f"Duplicate of: {', '.join(others)}")
+    if duplicates:
+        print(f"\n{len(duplicates)} set(s) of duplicate videos found in the catalog.")
+
+
+def run_chat(
+    editor: VideoEditor, session: EditSession, assume_yes: bool = False, dry_run: bool = False
+) -> List[Dict]:
+    """Run a conversational editing session where each prompt refines the previous plan."""
+    print(f"\nEditing session {session.session_id}")
+    if session.plan:
+        print(f"Resuming with the plan from: {session.turns[-1]['prompt'] if session.turns else session.summary}")
+    print("Describe your edit. Follow-up prompts refine the previous plan.")
+    print("Enter an empty line or 'exit' to finish.")
+
+    results = []
+    while True:
+        prompt = input("\nedit> ").strip()
+        if not prompt or prompt.lower() in ("exit", "quit"):
+            break
+        results.append(asyncio.run(
+            editor.process_edit(
+                prompt, [], skip_upload=True, assume_yes=assume_yes, dry_run=dry_run, session=session
+            )
+        ))
+
+    if session.path.exists():
+        print(f"\nSession saved. Resume it with: python main.py chat --resume {session.session_id}")
+    return results
+
+
+def print_dry_run(description: Dict) -> None:
+    """Print the ffmpeg invocations a render would run."""
+    print("\nDry run: no files will be written.")
+    print(f"Input: {description['input_path']}")
+    print(f"Output: {description['output_path']}")
+    print(f"Expected duration: {description['expected_duration']:.2f}s")
+    print("\nTemporary files:")
+    for temp_file in description['temp_files']:
+        print(f"  {temp_file}")
+    print("\nFFmpeg commands:")
+    for i, command in enumerate(description['commands']):
+        print(f"\n{i+1}. {command}")
+
+
+def add_logging_arguments(parser: argparse.ArgumentParser, default=None) -> None:
+    """Add the verbosity and log format flags to a parser."""
+    parser.add_argument(
+        "-v", "--verbose", action="count", default=0 if default is None else default,
+        help="Show debug output (-vv also shows library logs)",
+    )
+    parser.add_argument(
+        "-q", "--quiet", action="count", default=0 if default is None else default,
+        help="Only show warnings (-qq: only errors)",
+    )
+    parser.add_argument(
+        "--log-json", action="store_true", default=False if default is None else default,
+        help="Emit log lines as JSON objects",
+    )
+
+
+def build_parser() -> argparse.ArgumentParser:
+    """Build the command line parser for the non-interactive subcommands."""
+    parser = argparse.ArgumentParser(
+        prog="reduct",
+        description="Reduct AI Video Editor CLI Tool. Runs the interactive menu when no command is given.",
+    )
+    add_logging_arguments(parser)
+    parser.add_argument("--config", metavar="PATH", help="Project config file (default: the closest reduct.toml)")
+    parser.add_argument(
+        "--project", metavar="NAME", default=os.getenv("REDUCT_PROJECT"),
+        help="Work in a named project with its own index, catalog and folders (default: $REDUCT_PROJECT)",
+    )
+    subparsers = parser.add_subparsers(dest="command", metavar="command")
+
+    # Shared by every subcommand so --json and the logging flags can go after the command name
+    output_parser = argparse.ArgumentParser(add_help=False)
+    output_parser.add_argument(
+        "--json",
+        action="store_true",
+        help="Print a single JSON document with the result on stdout; logs go to stderr",
+    )
+    add_logging_arguments(output_parser, default=argparse.SUPPRESS)
+
+    upload_parser = subparsers.add_parser(
+        "upload", parents=[output_parser], help="Upload and index videos"
+    )
+    upload_parser.add_argument("paths", nargs="+", help="Video files, folders or glob patterns to upload")
+    upload_parser.add_argument(
+        "-r", "--recursive", action="store_true", help="Include videos in subfolders of folder arguments"
+    )
+    upload_parser.add_argument(
+        "-y", "--yes", action="store_true", help="Start the upload without asking for confirmation"
+    )
+
+    edit_parser = subparsers.add_parser(
+        "edit", parents=[output_parser], help="Edit indexed videos from a prompt"
+    )
+    edit_parser.add_argument("-p", "--prompt", required=True, help="Editing prompt")
+    edit_parser.add_argument(
+        "paths", nargs="*",
+        help="Video files, folders or glob patterns to upload before editing (default: use already indexed videos)",
+    )
+    edit_parser.add_argument(
+        "-r", "--recursive", action="store_true", help="Include videos in subfolders of folder arguments"
+    )
+    edit_parser.add_argument("-o", "--output", help="Output file for the edited video")
+    edit_parser.add_argument(
+        "-y", "--yes", action="store_true",
+        help="Start uploads without confirmation and render the generated plan without reviewing it",
+    )
+    edit_parser.add_argument("--save-plan", metavar="PATH", help="Write the generated edit plan to a JSON file")
+    edit_parser.add_argument(
+        "--dry-run", action="store_true", help="Print the ffmpeg commands instead of running them"
+    )
+
+    render_parser = subparsers.add_parser(
+        "render", parents=[output_parser], help="Render a saved edit plan without calling Gemini"
+    )
+    render_parser.add_argument("--plan", required=True, help="Edit plan JSON file")
+    render_parser.add_argument(
+        "--source",
+        help="Video ID or file path to cut from instead of the plan's video; only for plans cut from one video "
+        "(default: the videos and source recorded in the plan)",
+    )
+    render_parser.add_argument("-o", "--output", help="Output file for the edited video")
+    render_parser.add_argument(
+        "--dry-run", action="store_true", help="Print the ffmpeg commands instead of running them"
+    )
+
+    chat_parser = subparsers.add_parser(
+        "chat", parents=[output_parser], help="Start a conversational editing session"
+    )
+    chat_parser.add_argument("--resume", metavar="SESSION_ID", help="Resume a saved session")
+    chat_parser.add_argument("--list", action="store_true", help="List saved sessions")
+    chat_parser.add_argument(
+        "-y", "--yes", action="store_true", help="Render each plan without reviewing it"
+    )
+    chat_parser.add_argument(
+        "--dry-run", action="store_true", help="Print the ffmpeg commands instead of running them"
+    )
+
+    # Only the actions take the output flags; a parent's flags would be overwritten by its action's defaults
+    history_parser = subparsers.add_parser("history", help="List, compare and replay past edits")
+    history_subparsers = history_parser.add_subparsers(dest="history_command", metavar="action", required=True)
+    history_list_parser = history_subparsers.add_parser("list", parents=[output_parser], help="List recorded edits")
+    history_list_parser.add_argument("-n", "--limit", type=int, default=20, help="Edits to show (default: 20)")
+    history_show_parser = history_subparsers.add_parser(
+        "show", parents=[output_parser], help="Show the prompt, analysis, clips, plan and output of an edit"
+    )
+    history_show_parser.add_argument("edit_id", help="Edit ID from `history list`")
+    history_diff_parser = history_subparsers.add_parser("diff", parents=[output_parser], help="Compare two edits")
+    history_diff_parser.add_argument("edit_id", help="Older edit ID")
+    history_diff_parser.add_argument("other_edit_id", help="Newer edit ID")
+    history_replay_parser = history_subparsers.add_parser(
+        "replay", parents=[output_parser], help="Render the recorded plan of an edit again without calling Gemini"
+    )
+    history_replay_parser.add_argument("edit_id", help="Edit ID from `history list`")
+    history_replay_parser.add_argument("-o", "--output", help="Output file for the edited video")
+    history_replay_parser.add_argument(
+        "--dry-run", action="store_true", help="Print the ffmpeg commands instead of running them"
+    )
+
+    list_parser = subparsers.add_parser("list", parents=[output_parser], help="List uploaded videos")
+    list_parser.add_argument("--tag", help="Only list videos with this tag")
+
+    subparsers.add_parser("config", parents=[output_parser], help="Show the effective configuration")
+
+    subparsers.add_parser(
+        "resume", parents=[output_parser], help="Finish uploads interrupted by a previous run"
+    )
+
+    watch_parser = subparsers.add_parser(
+        "watch", parents=[output_parser], help="Upload and index new videos as they appear in watched folders"
+    )
+    watch_parser.add_argument(
+        "folders", nargs="*", help="Folders to watch (default: watch.folders from config)"
+    )
+    watch_parser.add_argument(
+        "-r", "--recursive", action="store_true", help="Also watch subfolders"
+    )
+    watch_parser.add_argument(
+        "--once", action="store_true", help="Exit once the videos found so far are uploaded"
+    )
+
+    remove_parser = subparsers.add_parser(
+        "remove", parents=[output_parser],
+        help="Delete videos from the index along with their catalog entries and cached data",
+    )
+    remove_parser.add_argument("targets", nargs="+", metavar="VIDEO", help="Video IDs or original file paths")
+    remove_parser.add_argument(
+        "-y", "--yes", action="store_true", help="Delete without asking for confirmation"
+    )
+
+    reconcile_parser = subparsers.add_parser(
+        "reconcile", parents=[output_parser],
+        help="Find mismatches between the Twelvelabs index, the catalog and the files on disk",
+    )
+    reconcile_parser.add_argument(
+        "--search", metavar="DIR", nargs="+",
+        help="Folders to search for moved files (default: watched folders and the folders of cataloged files)",
+    )
+    reconcile_parser.add_argument("--fix", action="store_true", help="Fix the mismatches found")
+    reconcile_parser.add_argument(
+        "-y", "--yes", action="store_true", help="Apply fixes without asking for confirmation"
+    )
+
+    link_parser = subparsers.add_parser(
+        "link", parents=[output_parser], help="Add metadata for an already uploaded video"
+    )
+    link_parser.add_argument("video_id", help="Video ID from a previous upload")
+    link_parser.add_argument("path", help="Original file path")
+
+    tag_parser = subparsers.add_parser("tag", parents=[output_parser], help="Tag videos in the catalog")
+    tag_parser.add_argument("video_id", help="Video ID")
+    tag_parser.add_argument("tags", nargs="*", help="Tags to add")
+    tag_parser.add_argument("--remove", nargs="+", default=[], metavar="TAG", help="Tags to remove")
+
+    search_parser = subparsers.add_parser(
+        "search", parents=[output_parser], help="Search indexed videos"
+    )
+    search_parser.add_argument("query", help="Search query")
+
+    # As with history, only the actions take the output flags
+    project_parser = subparsers.add_parser("project", help="Create and list projects")
+    project_subparsers = project_parser.add_subparsers(dest="project_command", metavar="action", required=True)
+    project_create_parser = project_subparsers.add_parser(
+        "create", parents=[output_parser], help="Create a project with its own index"
+    )
+    project_create_parser.add_argument("name", help="Project name: letters, digits, dashes and underscores")
+    project_create_parser.add_argument(
+        "--index-id", metavar="ID", help="Use an existing Twelvelabs index instead of creating one"
+    )
+    project_create_parser.add_argument("--output-dir", metavar="PATH", help="Where the project's edits are saved")
+    project_create_parser.add_argument("--temp-dir", metavar="PATH", help="The project's working folder")
+    project_subparsers.add_parser("list", parents=[output_parser], help="List projects")
+
+    return parser
+
+
+def resolve_cli_paths(paths: List[str], recursive: bool = False) -> Optional[List[str]]:
+    """Expand files, folders and globs into video paths, returning None if any input matched nothing."""
+    videos, unmatched = expand_video_paths(paths, recursive=recursive or None)
+    for path in unmatched:
+        logger.error(f"No videos found at: {path}")
+    return None if unmatched else videos
+
+
+def command_error(result: Dict, message: str) -> Dict:
+    """Report a command failure on stderr and record it in the command result."""
+    logger.error(message)
+    result["ok"] = False
+    result["errors"].append(message)
+    return result
+
+
+def run_project_command(args: argparse.Namespace, result: Dict) -> Dict:
+    """Create or list projects."""
+    if args.project_command == "create":
+        try:
+            check_new_project(args.name)
+            index_id = args.index_id or create_index(f"reduct-{args.name}")
+            project = create_project(args.name, index_id, output_dir=args.output_dir, temp_dir=args.temp_dir)
+        except Exception as e:
+            return command_error(result, f"Error creating project {args.name}: {str(e)}")
+        logger.info(f"Created project {project.name} with index {project.index_id}")
+        result["project"] = asdict(project)
+        return result
+
+    projects = load_projects()
+    if not projects:
+        print("\nNo projects yet. Create one with 'project create NAME'.")
+    for project in projects.values():
+        print(f"\n{project.name}")
+        print(f"  Index: {project.index_id}")
+        print(f"  Output: {project.output_dir}")
+        print(f"  Temp: {project.temp_dir}")
+        print(f"  Created: {project.created_at}")
+    result["projects"] = [asdict(project) for project in projects.values()]
+    return result
+
+
+def run_history_command(args: argparse.Namespace, editor: VideoEditor, result: Dict) -> Dict:
+    """List, show, diff or replay recorded edits."""
+    if args.history_command == "list":
+        edits = editor.list_edits()[:args.limit]
+        print_history(edits)
+        result["edits"] = [
+            {key: edit.get(key) for key in ("edit_id", "created_at", "status", "prompt", "output_path", "replay_of")}
+            for edit in edits
+        ]
+        return result
+
+    if args.history_command == "replay":
+        try:
+            replay = editor.replay_edit(args.edit_id, args.output, dry_run=args.dry_run)
+        except (KeyError, ValueError, FileNotFoundError) as e:
+            return command_error(result, e.args[0] if isinstance(e, KeyError) else str(e))
+        result.update(
+            (key, replay.get(key)) for key in ("edit_id", "status", "plan", "output_path", "sources", "dry_run")
+        )
+        result["replay_of"] = args.edit_id
+        result["timings"] = replay["timings"]
+        if replay["errors"]:
+            result["ok"] = False
+            result["errors"].extend(replay["errors"])
+        return result
+
+    edits = []
+    for edit_id in [args.edit_id] + ([args.other_edit_id] if args.history_command == "diff" else []):
+        edit = editor.history.get(edit_id)
+        if not edit:
+            return command_error(result, f"No edit {edit_id} in the history")
+        edits.append(edit)
+
+    if args.history_command == "show":
+        print_edit(edits[0])
+        result["edit"] = edits[0]
+        return result
+
+    lines = diff_edits(*edits)
+    print("\n".join(lines) if lines else f"\n{args.edit_id} and {args.other_edit_id} are identical.")
+    result["diff"] = lines
+    return result
+
+
+def run_command(args: argparse.Namespace, project: Optional[Project] = None) -> Dict:
+    """Run a single subcommand and return its structured result."""
+    result = {"command": args.command, "ok": True, "errors": [], "timings": {}}
+    if project:
+        result["project"] = project.name
+
+    if args.command == "config":
+        files = [str(path) for path in config_files(Path(args.config) if args.config else None)]
+        print(f"Config files: {', '.join(files) if files else '(defaults only)'}")
+        for section, settings in get_config().items():
+            print(f"\n[{section}]")
+            for key, value in settings.items():
+                print(f"{key} = {json.dumps(value)}")
+        result["files"] = files
+        result["config"] = get_config()
+        return result
+
+    if args.command == "project":
+        return run_project_command(args, result)
+
+    editor = VideoEditor(project)
+
+    if args.command == "search":
+        with timed(result["timings"], "search"):
+            clips = asyncio.run(editor.search_clips(args.query))
+        logger.info(f"Found {len(clips)} clip(s).")
+        if clips:
+            print_search_results(clips)
+        result["query"] = args.query
+        result["clips"] = clips
+        return result
+
+    if args.command == "upload":
+        video_paths = resolve_cli_paths(args.paths, args.recursive)
+        if video_paths is None:
+            return command_error(result, "One or more video files were not found.")
+        result["summary"] = summarize_uploads(video_paths)
+        if not confirm_uploads(video_paths, args.yes):
+            return command_error(result, "Upload cancelled.")
+
+        with timed(result["timings"], "upload"):
+            result["uploads"] = asyncio.run(editor.upload_videos(video_paths))
+
+        # Upload errors are already logged in the upload summary
+        for upload in result["uploads"]:
+            if upload["status"] in FAILED_STATUSES:
+                result["ok"] = False
+                result["errors"].append(f"Error uploading {upload['path']}: {upload['error']}")
+        return result
+
+    if args.command == "edit":
+        video_paths = resolve_cli_paths(args.paths, args.recursive)
+        if video_paths is None:
+            return command_error(result, "One or more video files were not found.")
+        if video_paths and not confirm_uploads(video_paths, args.yes):
+            return command_error(result, "Upload cancelled.")
+        edit_result = asyncio.run(
+            editor.process_edit(
+                args.prompt,
+                video_paths,
+                skip_upload=not video_paths,
+                output_path=args.output,
+                assume_yes=args.yes,
+                plan_path=args.save_plan,
+                dry_run=args.dry_run,
+            )
+        )
+        result.update(edit_result)
+        result["ok"] = edit_result["status"] in ("rendered", "planned", "cancelled")
+        return result
+
+    if args.command == "render":
+        try:
+            edit_plan = load_edit_plan(args.plan)
+        except (OSError, ValueError) as e:
+            return command_error(result, f"Error loading edit plan: {str(e)}")
+        result["plan"] = edit_plan
+
+        source = args.source or edit_plan.get("source")
+        if not source:
+            return command_error(result, "No --source given and the plan does not record one.")
+        input_path = editor.resolve_source(source)
+        if not input_path or not os.path.exists(input_path):
+            return command_error(result, f"Could not find a source video for {source}")
+        result["input_path"] = input_path
+        if args.source:
+            # An explicit source replaces the video the plan's trims were cut from
+            try:
+                retarget_plan(edit_plan, None if os.path.exists(args.source) else args.source)
+            except ValueError as e:
+                return command_error(result, str(e))
+        # Hand-edited plans can run past the end of a video; trims without a video_id cut from the source
+        editor.clamp_to_videos(edit_plan, None if os.path.exists(source) else source)
+
+        try:
+            sources = editor.collect_sources(edit_plan)
+        except FileNotFoundError as e:
+            return command_error(result, str(e))
+
+        if args.dry_run:
+            description = describe_plan(edit_plan, input_path, args.output or editor.default_output_path(), sources)
+            print_dry_run(description)
+            result["dry_run"] = description
+            return result
+
+        try:
+            with timed(result["timings"], "render"):
+                final_path = editor.render_plan(edit_plan, input_path, args.output, sources)
+        except Exception as e:
+            return command_error(result, f"Error during FFmpeg execution: {str(e)}")
+        logger.info(f"Edit completed successfully! Output saved to: {final_path}")
+        result["output_path"] = final_path
+        return result
+
+    if args.command == "chat":
+        if args.list:
+            sessions = list_sessions(editor.sessions_dir)
+            if not sessions:
+                print("\nNo saved sessions.")
+            for session in sessions:
+                print(f"\n{session['session_id']} ({session['turns']} turn(s), started {session['created_at']})")
+                print(f"  Last prompt: {session['last_prompt']}")
+            result["sessions"] = sessions
+            return result
+
+        if args.resume:
+            try:
+                session = EditSession.load(editor.sessions_dir, args.resume)
+            except (OSError, ValueError, TypeError) as e:
+                return command_error(result, f"Error loading session {args.resume}: {str(e)}")
+        else:
+            session = EditSession.create(editor.sessions_dir)
+        result["session_id"] = session.session_id
+        result["turns"] = run_chat(editor, session, assume_yes=args.yes, dry_run=args.dry_run)
+        return result
+
+    if args.command == "resume":
+        with timed(result["timings"], "resume"):
+            uploads = asyncio.run(editor.resume_uploads())
+        if not uploads:
+            logger.info("No interrupted uploads to resume.")
+        result["uploads"] = uploads
+        for upload in uploads:
+            if upload["status"] in FAILED_STATUSES:
+                command_error(result, f"Error resuming {upload['path']}: {upload['error']}")
+        return result
+
+    if args.command == "watch":
+        try:
+            with timed(result["timings"], "watch"):
+                status = asyncio.run(watch_folders(editor, args.folders, args.recursive, args.once))
+        except KeyboardInterrupt:
+            logger.info("Stopped watching.")
+            return result
+        result["files"] = status["files"]
+        for path, entry in status["files"].items():
+            if entry["status"] in FAILED_STATUSES:
+                result["ok"] = False
+                result["errors"].append(f"Error uploading {path}: {entry['error']}")
+        return result
+
+    if args.command == "list":
+        uploaded_videos = editor.list_uploaded_videos(args.tag)
+        if not uploaded_videos:
+            print(f"\nNo videos are tagged {args.tag}." if args.tag else "\nNo videos have been uploaded yet.")
+        else:
+            print_uploaded_videos(uploaded_videos)
+        result["videos"] = uploaded_videos
+        result["duplicates"] = list(find_duplicates(uploaded_videos).values())
+        return result
+
+    if args.command == "remove":
+        videos = []
+        for target in args.targets:
+            found = editor.find_videos(target)
+            if not found:
+                return command_error(result, f"No video found for {target}")
+            videos.extend(video for video in found if video["video_id"] not in {v["video_id"] for v in videos})
+
+        print(f"\n{len(videos)} video(s) to delete from the index and catalog:")
+        for video in videos:
+            print(f"  {video['video_id']}  {video.get('original_path', '')}")
+        if not confirm_action("\nDelete them? The original files are kept.", args.yes):
+            return command_error(result, "Removal cancelled.")
+
+        result["removed"] = []
+        for video in videos:
+            try:
+                editor.remove_video(video["video_id"])
+                result["removed"].append(video["video_id"])
+            except Exception as e:
+                command_error(result, f"Error removing {video['video_id']}: {str(e)}")
+        return result
+
+    if args.command == "reconcile":
+        try:
+            with timed(result["timings"], "reconcile"):
+                issues = editor.reconcile(args.search, fix=args.fix, assume_yes=args.yes)
+        except Exception as e:
+            return command_error(result, f"Error reconciling the catalog: {str(e)}")
+        print_mismatches(issues, fix=args.fix)
+        result["issues"] = issues
+        if args.fix and any(issue["fix"] and not issue.get("fixed") for issue in issues):
+            result["ok"] = False
+            result["errors"].append("Some mismatches were not fixed.")
+        return result
+
+    if args.command == "history":
+        return run_history_command(args, editor, result)
+
+    if args.command == "tag":
+        try:
+            tags = editor.tag_video(args.video_id, args.tags, args.remove)
+        except KeyError as e:
+            return command_error(result, e.args[0])
+        print(f"\n{args.video_id}: {', '.join(tags) if tags else '(no tags)'}")
+        result["video_id"] = args.video_id
+        result["tags"] = tags
+        return result
+
+    if args.command == "link":
+        original_path = os.path.abspath(args.path)
+        if not os.path.exists(original_path):
+            return command_error(result, f"File not found at {original_path}")
+        try:
+            editor.add_existing_video(args.video_id, original_path)
+        except Exception as e:
+            return command_error(result, f"Error adding existing video: {str(e)}")
+        result["video_id"] = args.video_id
+        result["original_path"] = original_path
+        return result
+
+    return command_error(result, f"Unknown command: {args.command}")
+
+
+def main(argv: Optional[List[str]] = None) -> int:
+    """Entry point: dispatch to a subcommand, or the interactive menu if none is given."""
+    args = build_parser().parse_args(argv)
+    set_config_path(args.config)
+    project = None
+    if args.project:
+        try:
+            project = get_project(args.project)
+        except KeyError as e:
+            print(f"Error: {e.args[0]}", file=sys.stderr)
+            return 1
+        # Before logging is set up, so the log file goes to the project's temp folder
+        activate_project(project)
+    log_path = setup_logging(
+        verbosity=args.verbose - args.quiet,
+        json_lines=args.log_json,
+        log_dir=Path(get_config()["paths"]["temp_dir"]) / "logs",
+    )
+    if log_path:
+        logger.debug(f"Writing log file to {log_path}")
+
+    if args.command is None:
+        main_menu(project)
+        return 0
+
+    if not args.json:
+        return 0 if run_command(args, project)["ok"] else 1
+
+    # Keep stdout for the JSON document; everything else goes to stderr
+    with redirect_stdout(sys.stderr):
+        try:
+            result = run_command(args, project)
+        except Exception as e:
+            logger.debug("Command failed", exc_info=True)
+            result = {"command": args.command, "ok": False, "errors": [str(e)], "timings": {}}
+    print(json.dumps(result, indent=2, default=str))
+    return 0 if result["ok"] else 1
+
+
 if __name__ == "__main__":
-    main_menu()
+    sys.exit(main())
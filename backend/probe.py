@@ -0,0 +1,278 @@
+import hashlib
+import os
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+import ffmpeg
+
+from config import get_config
+from logging_config import get_logger
+
+logger = get_logger("probe")
+
+
+class ProbeError(Exception):
+    """Raised when ffprobe can't read a file, usually because it is corrupt or not a video."""
+
+
+def last_error_line(error: ffmpeg.Error) -> str:
+    """The last line ffmpeg wrote to stderr, which usually names the problem."""
+    stderr = error.stderr.decode(errors="replace").strip() if getattr(error, "stderr", None) else ""
+    return stderr.splitlines()[-1] if stderr else "no details"
+
+
+def parse_rate(rate: Optional[str]) -> Optional[float]:
+    """Convert an ffprobe frame rate such as "30000/1001" to frames per second."""
+    if not rate:
+        return None
+    numerator, _, denominator = rate.partition("/")
+    try:
+        value = float(numerator) / float(denominator or 1)
+    except (ValueError, ZeroDivisionError):
+        return None
+    return round(value, 3) if value > 0 else None
+
+
+def parse_int(value) -> Optional[int]:
+    try:
+        return int(value)
+    except (TypeError, ValueError):
+        return None
+
+
+def stream_rotation(stream: Dict) -> int:
+    """The rotation a player applies to a video stream, from its display matrix or rotate tag."""
+    for side_data in stream.get("side_data_list", []):
+        if "rotation" in side_data:
+            return int(float(side_data["rotation"])) % 360
+    return (parse_int(stream.get("tags", {}).get("rotate")) or 0) % 360
+
+
+def probe_video(path: str) -> Dict:
+    """
+    Read a file's container and stream details with ffprobe.
+
+    Returns:
+        Dict: Duration, size, container format, overall bitrate and creation
+        time; the first video stream's codec, resolution, frame rate, pixel
+        format and rotation; the codecs of every audio stream and the first
+        audio stream's channel count and sample rate
+
+    Raises:
+        ProbeError: If ffprobe can't read the file or it has no video stream
+    """
+    try:
+        data = ffmpeg.probe(path)
+    except ffmpeg.Error as e:
+        raise ProbeError(f"ffprobe could not read the file ({last_error_line(e)})")
+    except OSError as e:
+        raise ProbeError(f"ffprobe could not be run: {str(e)}")
+
+    streams = data.get("streams", [])
+    video = next((s for s in streams if s.get("codec_type") == "video" and not s.get("disposition", {}).get("attached_pic")), None)
+    if not video:
+        raise ProbeError("the file has no video stream")
+    audio = [s for s in streams if s.get("codec_type") == "audio"]
+
+    format_info = data.get("format", {})
+    duration = format_info.get("duration") or video.get("duration")
+    creation_time = format_info.get("tags", {}).get("creation_time") or video.get("tags", {}).get("creation_time")
+    return {
+        "duration": float(duration) if duration else None,
+        "size": int(format_info["size"]) if format_info.get("size") else os.path.getsize(path),
+        "format_name": format_info.get("format_name"),
+        "bitrate": parse_int(format_info.get("bit_rate")),
+        "creation_time": creation_time,
+        "video_codec": video.get("codec_name"),
+        "width": video.get("width"),
+        "height": video.get("height"),
+        "fps": parse_rate(video.get("avg_frame_rate")) or parse_rate(video.get("r_frame_rate")),
+        "pix_fmt": video.get("pix_fmt"),
+        "rotation": stream_rotation(video),
+        "audio_codecs": [s.get("codec_name") for s in audio],
+        "audio_channels": audio[0].get("channels") if audio else None,
+        "audio_sample_rate": parse_int(audio[0].get("sample_rate")) if audio else None,
+    }
+
+
+def describe_technical(info: Dict) -> str:
+    """Summarize probed details on one line, e.g. "1920x1080 @ 29.97 fps, h264/yuv420p, 8.2 Mb/s"."""
+    parts = []
+    if info.get("width") and info.get("height"):
+        resolution = f"{info['width']}x{info['height']}"
+        if info.get("fps"):
+            resolution += f" @ {info['fps']:g} fps"
+        parts.append(resolution)
+    if info.get("rotation"):
+        parts.append(f"rotated {info['rotation']}°")
+    codec = "/".join(value for value in (info.get("video_codec"), info.get("pix_fmt")) if value)
+    if codec:
+        parts.append(codec)
+    if info.get("bitrate"):
+        parts.append(f"{info['bitrate'] / 1_000_000:.1f} Mb/s")
+    if info.get("audio_codecs"):
+        audio = f"audio {', '.join(info['audio_codecs'])}"
+        if info.get("audio_channels"):
+            audio += f" {info['audio_channels']}ch"
+        if info.get("audio_sample_rate"):
+            audio += f" {info['audio_sample_rate']} Hz"
+        parts.append(audio)
+    else:
+        parts.append("no audio")
+    return ", ".join(parts)
+
+
+def check_video(info: Dict) -> Tuple[List[str], List[str]]:
+    """
+    Check probed details against the [validation] rules.
+
+    Returns:
+        Tuple[List[str], List[str]]: Problems that can't be fixed (e.g. the
+        video is too short), and problems that transcoding to a mezzanine
+        would fix (e.g. an unsupported codec)
+    """
+    rules = get_config()["validation"]
+    fatal: List[str] = []
+    fixable: List[str] = []
+
+    duration = info.get("duration")
+    if not duration:
+        fatal.append("duration could not be read")
+    else:
+        if duration < rules["min_duration"]:
+            fatal.append(f"duration {duration:.1f}s is shorter than the minimum of {rules['min_duration']}s")
+        # Long files are split into chunks instead when chunking is enabled
+        if rules["max_duration"] and duration > rules["max_duration"] and not get_config()["chunking"]["enabled"]:
+            fatal.append(f"duration {duration:.0f}s is longer than the maximum of {rules['max_duration']}s")
+
+    width, height = info.get("width") or 0, info.get("height") or 0
+    if not width or not height:
+        fatal.append("resolution could not be read")
+    elif width < rules["min_width"] or height < rules["min_height"]:
+        fatal.append(f"resolution {width}x{height} is below the minimum of {rules['min_width']}x{rules['min_height']}")
+    elif width > rules["max_width"] or height > rules["max_height"]:
+        fixable.append(f"resolution {width}x{height} is above the maximum of {rules['max_width']}x{rules['max_height']}")
+
+    if rules["video_codecs"] and info.get("video_codec") not in rules["video_codecs"]:
+        fixable.append(f"video codec {info.get('video_codec')} is not supported")
+
+    audio_codecs = info.get("audio_codecs", [])
+    if rules["require_audio"] and not audio_codecs:
+        fatal.append("the file has no audio stream")
+    unsupported_audio = [codec for codec in audio_codecs if rules["audio_codecs"] and codec not in rules["audio_codecs"]]
+    if unsupported_audio:
+        fixable.append(f"audio codec {', '.join(unsupported_audio)} is not supported")
+
+    max_size = rules["max_size_mb"] * 1024 * 1024
+    if max_size and info.get("size", 0) > max_size:
+        fixable.append(f"file size {info['size'] / (1024 * 1024):.0f} MB is above the maximum of {rules['max_size_mb']} MB")
+
+    return fatal, fixable
+
+
+def derived_name(path: str) -> str:
+    """A file name stem for working copies of a file, unique per source path, e.g. "take1_ab12cd34"."""
+    digest = hashlib.sha1(os.path.abspath(path).encode()).hexdigest()[:8]
+    return f"{Path(path).stem}_{digest}"
+
+
+def mezzanine_path(path: str, temp_dir: Path) -> Path:
+    """Where the conformed copy of a file is written, unique per source path."""
+    return temp_dir / "mezzanine" / f"{derived_name(path)}.mp4"
+
+
+def proxy_path(path: str, temp_dir: Path) -> Path:
+    """Where the low-resolution proxy of a file is written, unique per source path."""
+    return temp_dir / "proxies" / f"{derived_name(path)}.mp4"
+
+
+def transcode(
+    path: str,
+    output_path: Path,
+    scale: str,
+    crf: int,
+    preset: str,
+    start: Optional[float] = None,
+    length: Optional[float] = None,
+) -> str:
+    """
+    Transcode a file, or the part of it from start for length seconds, to
+    H.264/AAC with a scale filter, leaving its timing untouched.
+
+    Timestamps in the transcoded file match the original (offset by start),
+    so a transcode can be indexed while renders still cut from the original.
+    The output is written under a temporary name and only moved into place
+    once ffmpeg finishes, so an interrupted run never leaves a truncated file
+    at output_path.
+    """
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    input_options = {}
+    if start is not None:
+        input_options["ss"] = start
+    if length is not None:
+        input_options["t"] = length
+    stream = ffmpeg.input(path, **input_options)
+    partial_path = output_path.with_name(f"{output_path.stem}.partial{output_path.suffix}")
+    stream = ffmpeg.output(
+        stream, str(partial_path),
+        vcodec="libx264", acodec="aac", pix_fmt="yuv420p",
+        crf=crf, preset=preset, vf=scale, movflags="+faststart",
+    )
+    logger.debug(f"Transcoding {path} to {output_path}")
+    try:
+        ffmpeg.run(stream, overwrite_output=True, quiet=True)
+    except ffmpeg.Error as e:
+        partial_path.unlink(missing_ok=True)
+        raise ProbeError(f"could not transcode the file ({last_error_line(e)})")
+    os.replace(partial_path, output_path)
+    return str(output_path)
+
+
+def conform_scale() -> str:
+    """A scale filter that shrinks the picture to fit max_width x max_height, if needed."""
+    rules = get_config()["validation"]
+    return (
+        f"scale='min({rules['max_width']},iw)':'min({rules['max_height']},ih)'"
+        ":force_original_aspect_ratio=decrease:force_divisible_by=2"
+    )
+
+
+def proxy_scale() -> str:
+    """A scale filter that shrinks the shorter side of the picture to proxy.short_side, if needed."""
+    side = get_config()["proxy"]["short_side"]
+    return f"scale='if(gt(iw,ih),-2,min({side},iw))':'if(gt(iw,ih),min({side},ih),-2)'"
+
+
+def conform_video(path: str, temp_dir: Path) -> str:
+    """
+    Transcode a file to an H.264/AAC mezzanine that fits the validation rules,
+    scaling the picture down to fit max_width x max_height if needed.
+
+    Returns:
+        str: The path of the mezzanine file
+    """
+    crf = get_config()["validation"]["conform_crf"]
+    return transcode(path, mezzanine_path(path, temp_dir), conform_scale(), crf, "medium")
+
+
+def make_proxy(path: str, temp_dir: Path) -> str:
+    """
+    Generate a low-resolution H.264/AAC proxy to index instead of the original.
+
+    The shorter side of the picture is scaled down to proxy.short_side, so
+    portrait and landscape videos both stay above the indexer's minimum size.
+    An existing proxy newer than the original is reused, unless its duration
+    doesn't match the original's.
+
+    Returns:
+        str: The path of the proxy file
+    """
+    output_path = proxy_path(path, temp_dir)
+    if output_path.exists() and output_path.stat().st_mtime >= os.path.getmtime(path):
+        try:
+            if abs(probe_video(str(output_path))["duration"] - probe_video(path)["duration"]) <= 1.0:
+                return str(output_path)
+        except (ProbeError, TypeError):
+            pass
+        logger.info(f"Regenerating the proxy of {path}: the existing one doesn't match it")
+    return transcode(path, output_path, proxy_scale(), get_config()["proxy"]["crf"], "veryfast")
@@ -1,17 +1,19 @@
-from typing import List, Dict
+from typing import List, Dict, Optional
 from twelve import search_video, client, INDEX_ID
+from config import get_config
 
 class ClipProcessor:
-    def __init__(self):
+    def __init__(self, index_id: Optional[str] = None):
+        self.index_id = index_id or INDEX_ID  # Searches only see videos in this index
         self.processed_clips: List[Dict] = []
         
-    def get_highest_scored_clips(self, query: str, min_score: float = 0.7, video_id: str = None) -> List[Dict]:
+    def get_highest_scored_clips(self, query: str, min_score: Optional[float] = None, video_id: str = None) -> List[Dict]:
         """
         Get clips with the highest scores from the search results.
         
         Args:
             query (str): The search query
-            min_score (float): Minimum score threshold (default: 0.7)
+            min_score (float, optional): Minimum score threshold (default: search.min_score from config)
             video_id (str, optional): If provided, only return clips from this video ID
             
         Returns:
@@ -19,15 +21,18 @@ class ClipProcessor:
         """
         # Reset processed clips list
         self.processed_clips = []
+        search_config = get_config()["search"]
+        if min_score is None:
+            min_score = search_config["min_score"]
         
         # Get search results
         search_params = {
-            "index_id": INDEX_ID,
+            "index_id": self.index_id,
             "options": ["visual", "audio"],
             "query_text": query,
             "group_by": "clip",
             "operator": "or",
-            "page_limit": 5,
+            "page_limit": search_config["page_limit"],
             "sort_option": "score",
         }
         
@@ -0,0 +1,144 @@
+import logging
+import os
+import sys
+import threading
+import time
+from typing import Dict, List, Optional
+
+from logging_config import get_logger, console_level
+
+logger = get_logger("progress")
+
+
+def format_duration(seconds: float) -> str:
+    """Format seconds as MM:SS, or HH:MM:SS for an hour or more."""
+    seconds = int(max(seconds, 0))
+    h, rem = divmod(seconds, 3600)
+    m, s = divmod(rem, 60)
+    return f"{h:d}:{m:02d}:{s:02d}" if h else f"{m:02d}:{s:02d}"
+
+
+class ProgressBar:
+    """
+    A single-line progress bar on stderr.
+
+    On a terminal the bar is redrawn in place; otherwise progress is logged
+    every 10% so CI logs and --json runs stay readable. Nothing is shown in
+    quiet mode.
+    """
+
+    def __init__(self, label: str, total: float, width: int = 30):
+        self.label = label
+        self.total = max(total, 0.0)
+        self.width = width
+        self.done = 0.0
+        self.suffix = ""
+        self.started = time.monotonic()
+        self.enabled = console_level() <= logging.INFO
+        self.interactive = self.enabled and sys.stderr.isatty()
+        self.last_logged_step = -1
+        self.lock = threading.Lock()
+
+    def fraction(self) -> float:
+        if self.total <= 0:
+            return 0.0
+        return min(self.done / self.total, 1.0)
+
+    def eta(self) -> Optional[float]:
+        """Estimate the seconds remaining from the average rate so far."""
+        fraction = self.fraction()
+        if fraction <= 0:
+            return None
+        elapsed = time.monotonic() - self.started
+        return elapsed * (1 - fraction) / fraction
+
+    def render(self) -> str:
+        fraction = self.fraction()
+        filled = int(self.width * fraction)
+        bar = "#" * filled + "-" * (self.width - filled)
+        eta = self.eta()
+        timing = f"{format_duration(time.monotonic() - self.started)} elapsed"
+        if eta is not None and fraction < 1:
+            timing += f", ETA {format_duration(eta)}"
+        suffix = f" {self.suffix}" if self.suffix else ""
+        return f"{self.label} [{bar}] {fraction * 100:5.1f}% {timing}{suffix}"
+
+    def draw(self) -> None:
+        if self.interactive:
+            sys.stderr.write("\r\033[K" + self.render())
+            sys.stderr.flush()
+        elif self.enabled:
+            step = int(self.fraction() * 10)
+            if step != self.last_logged_step:
+                self.last_logged_step = step
+                logger.info(self.render())
+
+    def update(self, done: float, suffix: Optional[str] = None) -> None:
+        """Set how much of the total is done and redraw."""
+        with self.lock:
+            self.done = done
+            if suffix is not None:
+                self.suffix = suffix
+            self.draw()
+
+    def write(self, message: str) -> None:
+        """Print a full line above the bar without breaking it."""
+        with self.lock:
+            if self.interactive:
+                sys.stderr.write("\r\033[K" + message + "\n")
+                self.draw()
+            else:
+                logger.info(message)
+
+    def close(self) -> None:
+        """Finish the bar, leaving its last state on screen."""
+        with self.lock:
+            if self.interactive:
+                sys.stderr.write("\r\033[K" + self.render() + "\n")
+                sys.stderr.flush()
+                self.interactive = False
+
+
+class UploadProgress:
+    """Per-file upload and indexing progress, driven by VideoStatus changes."""
+
+    FINISHED = ("ready", "timed_out", "error")
+
+    def __init__(self, paths: List[str]):
+        self.statuses: Dict[str, str] = {path: "pending" for path in paths}
+        self.bar = ProgressBar("Uploading", len(paths))
+        self.bar.update(0, self.summary())
+
+    def summary(self) -> str:
+        counts: Dict[str, int] = {}
+        for status in self.statuses.values():
+            counts[status] = counts.get(status, 0) + 1
+        return ", ".join(f"{count} {status}" for status, count in sorted(counts.items()))
+
+    def set_status(self, path: str, status: str, detail: Optional[str] = None) -> None:
+        """Record a file's new status and show the transition."""
+        if self.statuses.get(path) == status and not detail:
+            return
+        self.statuses[path] = status
+        message = f"  {os.path.basename(path)}: {status}"
+        if detail:
+            message += f" ({detail})"
+        self.bar.write(message)
+        finished = sum(1 for status in self.statuses.values() if status in self.FINISHED)
+        self.bar.update(finished, self.summary())
+
+    def close(self) -> None:
+        self.bar.close()
+
+
+class RenderProgress:
+    """Render progress across every ffmpeg step of a plan, measured in seconds of output."""
+
+    def __init__(self, total_seconds: float):
+        self.bar = ProgressBar("Rendering", total_seconds)
+
+    def __call__(self, done_seconds: float, step: str) -> None:
+        self.bar.update(done_seconds, step)
+
+    def close(self) -> None:
+        self.bar.close()
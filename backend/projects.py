@@ -0,0 +1,107 @@
+import json
+import os
+import re
+from dataclasses import asdict, dataclass
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, Optional
+
+from config import USER_CONFIG_PATH, get_config, override_config
+from logging_config import get_logger
+
+logger = get_logger("projects")
+
+PROJECT_NAME_PATTERN = re.compile(r"^[A-Za-z0-9][A-Za-z0-9_-]*$")
+
+
+@dataclass
+class Project:
+    """A named project with its own index, catalog collections and working directories."""
+    name: str
+    index_id: str
+    output_dir: str
+    temp_dir: str
+    created_at: str
+
+    @property
+    def collection_prefix(self) -> str:
+        """Prefix for the project's catalog collections, e.g. "clientA_metadata"."""
+        return f"{self.name}_"
+
+
+def projects_path() -> Path:
+    """The project registry: paths.projects_file, or projects.json next to the user config."""
+    configured = get_config()["paths"]["projects_file"]
+    return Path(os.path.expanduser(configured)) if configured else USER_CONFIG_PATH.parent / "projects.json"
+
+
+def load_projects() -> Dict[str, Project]:
+    """Read every registered project, keyed by name."""
+    path = projects_path()
+    if not path.exists():
+        return {}
+    try:
+        with open(path) as f:
+            return {name: Project(**project) for name, project in json.load(f).items()}
+    except (OSError, ValueError, TypeError) as e:
+        logger.warning(f"Ignoring project registry {path}: {str(e)}")
+        return {}
+
+
+def save_projects(projects: Dict[str, Project]) -> None:
+    path = projects_path()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    with open(path, "w") as f:
+        json.dump({name: asdict(project) for name, project in projects.items()}, f, indent=2)
+
+
+def get_project(name: str) -> Project:
+    """Look up a registered project, raising KeyError with the known names if it doesn't exist."""
+    projects = load_projects()
+    if name not in projects:
+        known = ", ".join(sorted(projects)) or "none"
+        raise KeyError(f"Unknown project {name!r} (projects: {known}); create it with `project create {name}`")
+    return projects[name]
+
+
+def check_new_project(name: str) -> None:
+    """Raise ValueError if a name is invalid or already taken, before anything is created for it."""
+    if not PROJECT_NAME_PATTERN.match(name):
+        raise ValueError(f"Invalid project name {name!r}: use letters, digits, dashes and underscores")
+    if name in load_projects():
+        raise ValueError(f"Project {name!r} already exists")
+
+
+def create_project(
+    name: str, index_id: str, output_dir: Optional[str] = None, temp_dir: Optional[str] = None
+) -> Project:
+    """
+    Register a new project.
+
+    Args:
+        name (str): Letters, digits, dashes and underscores
+        index_id (str): The Twelvelabs index holding the project's videos
+        output_dir (str, optional): Where edits are saved
+            (default: <paths.output_dir>/<name>)
+        temp_dir (str, optional): Working files, sessions and logs
+            (default: <paths.temp_dir>/<name>)
+    """
+    check_new_project(name)
+    projects = load_projects()
+    paths_config = get_config()["paths"]
+    project = Project(
+        name=name,
+        index_id=index_id,
+        output_dir=os.path.abspath(output_dir or os.path.join(paths_config["output_dir"], name)),
+        temp_dir=os.path.abspath(temp_dir or os.path.join(paths_config["temp_dir"], name)),
+        created_at=datetime.utcnow().isoformat(),
+    )
+    projects[name] = project
+    save_projects(projects)
+    return project
+
+
+def activate_project(project: Project) -> None:
+    """Point the output and temp directories at the project's own for the rest of this run."""
+    override_config({"paths": {"output_dir": project.output_dir, "temp_dir": project.temp_dir}})
+    logger.debug(f"Using project {project.name} (index {project.index_id})")
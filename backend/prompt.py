@@ -1,9 +1,11 @@
 from google import genai
 import os
 from dotenv import load_dotenv
-from typing import List, Dict
+from typing import List, Dict, Optional
 import ffmpeg
 import json
+from logging_config import get_logger
+from config import get_config
 
 load_dotenv()
 
@@ -12,9 +14,66 @@ if not GEMINI_API_KEY:
     raise ValueError("GEMINI_API_KEY environment variable is not set")
 
 gemini_client = genai.Client(api_key=GEMINI_API_KEY)
+logger = get_logger("prompt")
 
 
-def generate_prompt(query: str, clip_data: List[Dict]) -> str:
+def revision_context(previous_plan: Optional[Dict], history: Optional[str]) -> str:
+    """Describe the plan being refined so a follow-up request revises it instead of starting over."""
+    if not previous_plan:
+        return ""
+    return f"""
+This is a follow-up request in an ongoing editing session.
+
+Conversation so far:
+{history or "(none)"}
+
+Current edit plan:
+{json.dumps(previous_plan, indent=2)}
+
+Revise the current edit plan according to the new request. Keep the clips, order
+and timings the user has not asked to change, and only use the video segments
+below when the request calls for new or different footage.
+"""
+
+
+def video_context(videos: Optional[Dict[str, Dict]]) -> str:
+    """Describe the source videos so the plan stays within each one's duration."""
+    if not videos:
+        return ""
+    return f"""
+Source videos, by video_id (duration in seconds). Never use a timestamp past the
+end of the video a trim is cut from:
+{json.dumps(videos, indent=2)}
+"""
+
+
+def summarize_history(summary: str, prompts: List[str]) -> str:
+    """Fold earlier session requests into a short running summary."""
+    prompt = f"""
+Summarize this video editing conversation in a few sentences, keeping every
+constraint the user has asked for (length, style, clips to include or avoid).
+
+Existing summary: {summary or "(none)"}
+
+Further requests, in order:
+{json.dumps(prompts, indent=2)}
+
+Return only the summary text.
+"""
+    response = gemini_client.models.generate_content(
+        model=get_config()["models"]["planner"],
+        contents=[prompt],
+    )
+    return response.text.strip()
+
+
+def generate_prompt(
+    query: str,
+    clip_data: List[Dict],
+    previous_plan: Optional[Dict] = None,
+    history: Optional[str] = None,
+    videos: Optional[Dict[str, Dict]] = None,
+) -> str:
     prompt = f"""
     You are a video editing assistant. Your task is to generate a JSON edit plan based on the user's request and video segments.
 
@@ -32,7 +91,8 @@ Each action in the array must follow one of these structures:
   "type": "trim",
   "start": "HH:MM:SS",
   "end": "HH:MM:SS",
-  "output": "segment_X.mp4"  // Unique output filename for this segment
+  "output": "segment_X.mp4",  // Unique output filename for this segment
+  "video_id": "..."  // video_id of the video segment this trim is cut from
 }}
 
 2. For concatenating trimmed segments (ALWAYS use this after trim):
@@ -94,6 +154,7 @@ Example of a valid response:
   ]
 }}
 
+{revision_context(previous_plan, history)}{video_context(videos)}
 Now generate the edit plan for:
 User query: {query}
 
@@ -115,7 +176,7 @@ IMPORTANT:
 
     try:
         response = gemini_client.models.generate_content(
-            model="gemini-2.0-flash",
+            model=get_config()["models"]["planner"],
             contents=[prompt],
         )
         
@@ -130,9 +191,9 @@ IMPORTANT:
         
         return response_text
     except json.JSONDecodeError as e:
-        print(f"Error: Invalid JSON response from model: {str(e)}")
-        print("Raw response:", response_text)
+        logger.error(f"Invalid JSON response from model: {str(e)}")
+        logger.debug(f"Raw response: {response_text}")
         raise
     except Exception as e:
-        print(f"Error generating edit plan: {str(e)}")
+        logger.error(f"Error generating edit plan: {str(e)}")
         raise
@@ -0,0 +1,172 @@
+import os
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from fingerprint import fingerprint_file
+from ingest import is_video_file
+from logging_config import get_logger
+
+logger = get_logger("reconcile")
+
+# What --fix does for each kind of mismatch
+FIX_DESCRIPTIONS = {
+    "relink": "point the catalog entry at the moved file",
+    "link": "add a catalog entry for the file",
+    "delete_metadata": "remove the catalog entry",
+    "delete_index": "delete the video from the index",
+    "delete": "delete the video from the index and remove the catalog entry",
+}
+
+
+class FileFinder:
+    """
+    Look up local video files by content or by name.
+
+    Files are only hashed when a catalog entry with the same size is being
+    looked up, so scanning a large folder stays cheap.
+    """
+
+    def __init__(self, directories: List[str], extensions: List[str]):
+        self.paths: List[str] = []
+        for directory in directories:
+            root = Path(os.path.expanduser(directory))
+            if root.is_dir():
+                self.paths.extend(str(path.resolve()) for path in root.rglob("*") if is_video_file(path, extensions))
+        self.paths = sorted(set(self.paths))
+        self.hashes: Dict[str, str] = {}
+
+    def find_by_content(self, content_hash: str, size: int) -> Optional[str]:
+        for path in self.paths:
+            try:
+                if os.path.getsize(path) != size:
+                    continue
+                if path not in self.hashes:
+                    self.hashes[path] = fingerprint_file(path)["content_hash"]
+            except OSError:
+                continue
+            if self.hashes[path] == content_hash:
+                return path
+        return None
+
+    def find_by_name(self, filename: str) -> List[str]:
+        return [path for path in self.paths if os.path.basename(path) == filename]
+
+
+def find_mismatches(
+    index_videos: List[Dict],
+    catalog: List[Dict],
+    finder: FileFinder,
+    pending: Optional[Dict[str, str]] = None,
+    uploads_unresolved: bool = False,
+) -> List[Dict]:
+    """
+    Compare the Twelvelabs index, the catalog and the filesystem.
+
+    Args:
+        index_videos (List[Dict]): Videos in the index, with video_id and filename
+        catalog (List[Dict]): Documents from the metadata collection
+        finder (FileFinder): Local files to relink moved or unknown videos to
+        pending (Dict[str, str], optional): Video IDs of unfinished upload
+            jobs, mapped to the file being uploaded; these are reported as
+            pending and never fixed, since `resume` finishes them
+        uploads_unresolved (bool): Some unfinished upload jobs could not be
+            matched to a video ID, so no indexed video is deleted for lack of
+            a catalog entry
+
+    Returns:
+        List[Dict]: One entry per mismatch with its kind, video_id, path, a
+        detail message and the fix --fix would apply (None if it can't be fixed
+        automatically), plus new_path for relinks
+    """
+    indexed = {video["video_id"]: video for video in index_videos}
+    cataloged = {entry["video_id"] for entry in catalog}
+    issues = []
+
+    for entry in catalog:
+        video_id = entry["video_id"]
+        path = entry.get("original_path")
+        exists = bool(path) and os.path.exists(path)
+        issue = {"video_id": video_id, "path": path, "new_path": None}
+
+        # Chunks are checked along with their parent
+        if entry.get("parent_id"):
+            if entry["parent_id"] not in cataloged:
+                issues.append(dict(
+                    issue, kind="orphan_chunk", detail=f"chunk of {entry['parent_id']}, which is not in the catalog",
+                    fix="delete",
+                ))
+            continue
+
+        chunk_ids = [chunk["video_id"] for chunk in entry.get("chunks", [])]
+        missing_chunks = [chunk_id for chunk_id in chunk_ids if chunk_id not in indexed]
+        if missing_chunks:
+            detail = f"{len(missing_chunks)} of {len(chunk_ids)} chunks are not in the index"
+            issues.append(dict(issue, kind="not_in_index", detail=detail, fix="delete"))
+        elif not chunk_ids and video_id not in indexed:
+            detail = "not in the index"
+            if exists:
+                detail += "; upload the file again to make it searchable"
+            issues.append(dict(issue, kind="not_in_index", detail=detail, fix="delete_metadata"))
+        elif not exists:
+            new_path = None
+            if entry.get("content_hash") and entry.get("size"):
+                new_path = finder.find_by_content(entry["content_hash"], entry["size"])
+            if new_path:
+                issues.append(dict(
+                    issue, kind="moved", detail=f"file moved to {new_path}", fix="relink", new_path=new_path
+                ))
+            else:
+                issues.append(dict(issue, kind="missing_file", detail="original file not found", fix="delete"))
+
+    pending = pending or {}
+    for video_id, video in indexed.items():
+        if video_id in cataloged:
+            continue
+        if video_id in pending:
+            issues.append({
+                "video_id": video_id, "path": pending[video_id], "kind": "pending", "new_path": None, "fix": None,
+                "detail": "indexed for an upload that hasn't finished; run resume to finish it",
+            })
+            continue
+        matches = finder.find_by_name(video["filename"]) if video.get("filename") else []
+        issue = {"video_id": video_id, "path": None, "kind": "not_in_catalog"}
+        if len(matches) == 1:
+            issues.append(dict(
+                issue, detail=f"indexed as {video['filename']}, found at {matches[0]}", fix="link", new_path=matches[0]
+            ))
+        elif matches:
+            issues.append(dict(
+                issue, detail=f"indexed as {video['filename']}, which matches {len(matches)} files; link it by hand",
+                fix=None, new_path=None,
+            ))
+        elif uploads_unresolved:
+            issues.append(dict(
+                issue, detail=f"indexed as {video.get('filename') or 'an unknown file'}, no local source found; "
+                "not deleted while uploads are unfinished, it may belong to one",
+                fix=None, new_path=None,
+            ))
+        else:
+            issues.append(dict(
+                issue, detail=f"indexed as {video.get('filename') or 'an unknown file'}, no local source found",
+                fix="delete_index", new_path=None,
+            ))
+    return issues
+
+
+def print_mismatches(issues: List[Dict], fix: bool) -> None:
+    """Print each mismatch with the fix that was or would be applied."""
+    if not issues:
+        print("\nThe index, catalog and files are in sync.")
+        return
+    print(f"\n{len(issues)} mismatch(es) found:")
+    for issue in issues:
+        print(f"\n  {issue['video_id']} [{issue['kind']}] {issue['path'] or ''}".rstrip())
+        print(f"    {issue['detail']}")
+        if issue["fix"]:
+            action = FIX_DESCRIPTIONS[issue["fix"]]
+            if fix:
+                print(f"    Fixed: {action}" if issue.get("fixed") else f"    Not fixed: {issue.get('error') or action}")
+            else:
+                print(f"    Fix: {action}")
+    if not fix and any(issue["fix"] for issue in issues):
+        print("\nRun with --fix to apply the fixes.")
@@ -0,0 +1,213 @@
+from typing import List, Dict, Optional, Tuple
+import copy
+
+from edit_generator import time_to_seconds, seconds_to_timestamp
+
+
+def match_clip(trim: Dict, clips: List[Dict]) -> Optional[Dict]:
+    """
+    Find the search result a trim action was cut from.
+
+    Trims that carry a video_id only match clips from that video. The clip
+    with the largest overlap with the trim's time range wins.
+    """
+    start = time_to_seconds(trim["start"])
+    end = time_to_seconds(trim["end"])
+    best_clip = None
+    best_overlap = 0.0
+    for clip in clips:
+        if trim.get("video_id") and clip["video_id"] != trim["video_id"]:
+            continue
+        overlap = min(end, clip["end_time"]) - max(start, clip["start_time"])
+        if overlap > best_overlap:
+            best_clip = clip
+            best_overlap = overlap
+    return best_clip
+
+
+def split_plan(edit_plan: Dict, clips: List[Dict]) -> Tuple[List[Dict], List[Dict]]:
+    """
+    Split an edit plan into its trims, in concat order, and its other actions.
+
+    Each trim is tagged with the video_id of the clip it matches so it keeps
+    its source after being reordered or edited.
+    """
+    trims = {}
+    other_actions = []
+    concat_order = []
+    for action in edit_plan.get("actions", []):
+        if action["type"] == "trim":
+            trim = dict(action)
+            clip = match_clip(trim, clips)
+            if clip and not trim.get("video_id"):
+                trim["video_id"] = clip["video_id"]
+            trims[trim["output"]] = trim
+        elif action["type"] == "concat":
+            segments = sorted(action.get("segments", []), key=lambda x: x["position"])
+            concat_order = [segment["file"] for segment in segments]
+        else:
+            other_actions.append(copy.deepcopy(action))
+
+    # Trims the concat doesn't reference go at the end so they stay visible
+    ordered = [trims[name] for name in concat_order if name in trims]
+    ordered.extend(trim for name, trim in trims.items() if name not in concat_order)
+    return ordered, other_actions
+
+
+def build_plan(trims: List[Dict], other_actions: List[Dict]) -> Dict:
+    """Rebuild an edit plan from an ordered list of trims, concatenating them in that order."""
+    actions = list(trims)
+    actions.append({
+        "type": "concat",
+        "segments": [{"file": trim["output"], "position": i} for i, trim in enumerate(trims)],
+    })
+    actions.extend(other_actions)
+    return {"actions": actions}
+
+
+def unique_output_name(trims: List[Dict]) -> str:
+    """Pick a segment filename no trim is using yet."""
+    used = {trim["output"] for trim in trims}
+    i = len(trims)
+    while f"segment_{i}.mp4" in used:
+        i += 1
+    return f"segment_{i}.mp4"
+
+
+def print_thumbnails(clip: Optional[Dict]) -> None:
+    """Print the local thumbnails of a clip, if any were extracted."""
+    if clip and clip.get("thumbnails"):
+        print(f"      {'  '.join(clip['thumbnails'].values())}")
+
+
+def print_trims(trims: List[Dict], clips: List[Dict]) -> None:
+    """Print the plan's trims with their source, time range and search score."""
+    print("\nEdit plan:")
+    if not trims:
+        print("  (empty)")
+    total = 0.0
+    for i, trim in enumerate(trims):
+        duration = time_to_seconds(trim["end"]) - time_to_seconds(trim["start"])
+        total += duration
+        clip = match_clip(trim, clips)
+        score = f"{clip['score']:.2f}" if clip else "-"
+        source = trim.get("video_id", "-")
+        print(f"  {i+1:>2}. {source}  {trim['start']} - {trim['end']}  ({duration:.1f}s)  score {score}")
+        print_thumbnails(clip)
+    print(f"  Total duration: {total:.1f}s")
+
+
+def print_search_results(clips: List[Dict]) -> None:
+    """Print the search results that can be added back into the plan."""
+    print("\nSearch results:")
+    for i, clip in enumerate(clips):
+        print(
+            f"  {i+1:>2}. {clip['video_id']}  "
+            f"{seconds_to_timestamp(clip['start_time'])} - {seconds_to_timestamp(clip['end_time'])}  "
+            f"score {clip['score']:.2f}"
+        )
+        print_thumbnails(clip)
+
+
+def print_review_help() -> None:
+    """Print the review commands."""
+    print("\nCommands:")
+    print("  d N        drop clip N")
+    print("  m N POS    move clip N to position POS")
+    print("  i N SECS   nudge the in point of clip N by SECS (e.g. i 2 -1.5)")
+    print("  o N SECS   nudge the out point of clip N by SECS")
+    print("  a K        add search result K to the end of the plan")
+    print("  s          show search results")
+    print("  r          render this plan")
+    print("  q          cancel without rendering")
+
+
+def nudge(trim: Dict, field: str, seconds: float) -> Optional[str]:
+    """Move a trim's in or out point, returning an error message if the result is invalid."""
+    start = time_to_seconds(trim["start"])
+    end = time_to_seconds(trim["end"])
+    if field == "start":
+        start += seconds
+    else:
+        end += seconds
+    if start < 0:
+        return "In point cannot be before the start of the video."
+    if end <= start:
+        return "Out point must be after the in point."
+    trim["start"] = seconds_to_timestamp(start)
+    trim["end"] = seconds_to_timestamp(end)
+    return None
+
+
+def parse_index(value: str, items: List) -> int:
+    """Convert a 1-based number typed by the user into a list index."""
+    index = int(value) - 1
+    if not 0 <= index < len(items):
+        raise IndexError(value)
+    return index
+
+
+def review_plan(edit_plan: Dict, clips: List[Dict]) -> Optional[Dict]:
+    """
+    Interactively review an edit plan clip by clip before rendering.
+
+    Args:
+        edit_plan (Dict): The generated edit plan
+        clips (List[Dict]): The search results the plan was generated from
+
+    Returns:
+        Optional[Dict]: The reviewed plan to render, or None if the user cancelled
+    """
+    trims, other_actions = split_plan(edit_plan, clips)
+    print_trims(trims, clips)
+    print_review_help()
+
+    while True:
+        parts = input("\nreview> ").strip().split()
+        if not parts:
+            continue
+        command, params = parts[0].lower(), parts[1:]
+
+        try:
+            if command == "r":
+                if not trims:
+                    print("The plan has no clips to render.")
+                    continue
+                return build_plan(trims, other_actions)
+            elif command == "q":
+                return None
+            elif command == "s":
+                print_search_results(clips)
+                continue
+            elif command in ("h", "?"):
+                print_review_help()
+                continue
+            elif command == "d":
+                trims.pop(parse_index(params[0], trims))
+            elif command == "m":
+                index = parse_index(params[0], trims)
+                position = max(int(params[1]) - 1, 0)
+                trims.insert(position, trims.pop(index))
+            elif command in ("i", "o"):
+                trim = trims[parse_index(params[0], trims)]
+                error = nudge(trim, "start" if command == "i" else "end", float(params[1]))
+                if error:
+                    print(error)
+                    continue
+            elif command == "a":
+                clip = clips[parse_index(params[0], clips)]
+                trims.append({
+                    "type": "trim",
+                    "start": seconds_to_timestamp(clip["start_time"]),
+                    "end": seconds_to_timestamp(clip["end_time"]),
+                    "output": unique_output_name(trims),
+                    "video_id": clip["video_id"],
+                })
+            else:
+                print("Unknown command. Type h for help.")
+                continue
+        except (IndexError, ValueError):
+            print("Invalid clip number or value. Type h for help.")
+            continue
+
+        print_trims(trims, clips)
@@ -0,0 +1,120 @@
+import json
+import uuid
+from dataclasses import dataclass, field, asdict
+from datetime import datetime
+from pathlib import Path
+from typing import List, Dict, Optional
+
+from prompt import summarize_history
+from logging_config import get_logger
+
+logger = get_logger("session")
+
+# Turns kept verbatim in the planner context; older turns are folded into the summary
+MAX_RECENT_TURNS = 4
+
+
+def merge_clips(existing: List[Dict], new: List[Dict]) -> List[Dict]:
+    """Append new search results to a clip list, skipping clips already in it."""
+    merged = list(existing)
+    seen = {(clip['video_id'], clip['start_time'], clip['end_time']) for clip in existing}
+    for clip in new:
+        key = (clip['video_id'], clip['start_time'], clip['end_time'])
+        if key not in seen:
+            merged.append(clip)
+            seen.add(key)
+    return merged
+
+
+@dataclass
+class EditSession:
+    """A conversational editing session whose plan is refined over several prompts."""
+    session_id: str
+    sessions_dir: str
+    created_at: str
+    clips: List[Dict] = field(default_factory=list)
+    plan: Optional[Dict] = None
+    turns: List[Dict] = field(default_factory=list)
+    summary: str = ""
+
+    @classmethod
+    def create(cls, sessions_dir: Path) -> "EditSession":
+        """Start a new, empty session."""
+        return cls(
+            session_id=datetime.now().strftime("%Y%m%d_%H%M%S_") + uuid.uuid4().hex[:6],
+            sessions_dir=str(sessions_dir),
+            created_at=datetime.now().isoformat(),
+        )
+
+    @classmethod
+    def load(cls, sessions_dir: Path, session_id: str) -> "EditSession":
+        """Load a saved session so the chat can be resumed."""
+        with open(Path(sessions_dir) / f"{session_id}.json") as f:
+            data = json.load(f)
+        data["sessions_dir"] = str(sessions_dir)
+        return cls(**data)
+
+    @property
+    def path(self) -> Path:
+        return Path(self.sessions_dir) / f"{self.session_id}.json"
+
+    def save(self) -> None:
+        """Persist the session to disk."""
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        data = asdict(self)
+        del data["sessions_dir"]
+        with open(self.path, "w") as f:
+            json.dump(data, f, indent=2)
+
+    def record_turn(self, prompt: str, clips: List[Dict], plan: Dict, output_path: Optional[str]) -> None:
+        """Record a finished edit and make its plan the one the next prompt refines."""
+        self.clips = clips
+        self.plan = plan
+        self.turns.append({
+            "prompt": prompt,
+            "plan": plan,
+            "output_path": output_path,
+            "created_at": datetime.now().isoformat(),
+        })
+        self.compact()
+
+    def compact(self) -> None:
+        """Summarize older turns once the history grows past MAX_RECENT_TURNS."""
+        if len(self.turns) <= MAX_RECENT_TURNS:
+            return
+        older = self.turns[:-MAX_RECENT_TURNS]
+        try:
+            self.summary = summarize_history(self.summary, [turn["prompt"] for turn in older])
+        except Exception as e:
+            # Keep the raw prompts rather than losing them if summarizing fails
+            logger.warning(f"Could not summarize session history: {str(e)}")
+            self.summary = "\n".join(filter(None, [self.summary] + [turn["prompt"] for turn in older]))
+        self.turns = self.turns[-MAX_RECENT_TURNS:]
+
+    def context(self) -> str:
+        """Describe the conversation so far for the planner."""
+        lines = []
+        if self.summary:
+            lines.append(f"Summary of earlier requests: {self.summary}")
+        for i, turn in enumerate(self.turns):
+            lines.append(f"Request {i+1}: {turn['prompt']}")
+        return "\n".join(lines)
+
+
+def list_sessions(sessions_dir: Path) -> List[Dict]:
+    """List saved sessions, most recent first."""
+    sessions = []
+    for path in sorted(Path(sessions_dir).glob("*.json"), reverse=True):
+        try:
+            with open(path) as f:
+                data = json.load(f)
+        except (OSError, json.JSONDecodeError):
+            continue
+        last_prompt = data["turns"][-1]["prompt"] if data.get("turns") else ""
+        sessions.append({
+            "session_id": data["session_id"],
+            "created_at": data["created_at"],
+            "turns": len(data.get("turns", [])),
+            "last_prompt": last_prompt,
+        })
+    return sessions
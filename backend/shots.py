@@ -0,0 +1,73 @@
+import bisect
+from typing import Dict, List, Optional
+
+from scenedetect import ContentDetector, detect
+
+from config import get_config
+from logging_config import get_logger
+
+logger = get_logger("shots")
+
+
+def detect_shots(path: str) -> Optional[List[float]]:
+    """
+    Find the shot boundaries in a video with PySceneDetect.
+
+    Returns:
+        Optional[List[float]]: The start time in seconds of every shot after
+        the first, in order, or None if detection is disabled or failed
+    """
+    shots_config = get_config()["shots"]
+    if not shots_config["enabled"]:
+        return None
+    try:
+        detector = ContentDetector(threshold=shots_config["threshold"], min_scene_len=shots_config["min_scene_len"])
+        scenes = detect(path, detector)
+    except Exception as e:
+        logger.warning(f"Could not detect shots in {path}: {str(e)}")
+        return None
+    boundaries = [round(start.get_seconds(), 3) for start, _ in scenes[1:]]
+    logger.debug(f"Detected {len(boundaries) + 1} shot(s) in {path}")
+    return boundaries
+
+
+def nearest_boundary(seconds: float, boundaries: List[float], tolerance: float) -> float:
+    """The closest boundary to a time if one is within tolerance seconds, otherwise the time itself."""
+    i = bisect.bisect_left(boundaries, seconds)
+    candidates = boundaries[max(i - 1, 0):i + 1]
+    if not candidates:
+        return seconds
+    closest = min(candidates, key=lambda boundary: abs(boundary - seconds))
+    return closest if abs(closest - seconds) <= tolerance else seconds
+
+
+def snap_clip(clip: Dict, boundaries: List[float], duration: Optional[float] = None) -> Dict:
+    """
+    Move a search result's start and end to the nearest shot boundaries.
+
+    The start and end of the video count as boundaries too. The times
+    returned by search are kept as search_start_time and search_end_time, and
+    a clip that would collapse to nothing is left as it was.
+    """
+    tolerance = get_config()["shots"]["snap_tolerance"]
+    edges = sorted({0.0, *boundaries, *([duration] if duration else [])})
+    start = nearest_boundary(clip["start_time"], edges, tolerance)
+    end = nearest_boundary(clip["end_time"], edges, tolerance)
+    if end <= start or (start == clip["start_time"] and end == clip["end_time"]):
+        return clip
+    snapped = dict(clip, start_time=start, end_time=end)
+    snapped["search_start_time"] = clip["start_time"]
+    snapped["search_end_time"] = clip["end_time"]
+    return snapped
+
+
+def snap_clips(clips: List[Dict], shots: Dict[str, List[float]], durations: Dict[str, float]) -> List[Dict]:
+    """Snap every clip from a video with known shot boundaries; other clips are returned unchanged."""
+    snapped = []
+    for clip in clips:
+        boundaries = shots.get(clip["video_id"])
+        if boundaries is None:
+            snapped.append(clip)
+        else:
+            snapped.append(snap_clip(clip, boundaries, durations.get(clip["video_id"])))
+    return snapped
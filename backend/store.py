@@ -0,0 +1,267 @@
+import json
+import os
+import sqlite3
+import threading
+from abc import ABC, abstractmethod
+from contextlib import contextmanager
+from datetime import datetime
+from pathlib import Path
+from typing import Any, Dict, Iterator, List, Optional
+
+try:
+    import fcntl
+except ImportError:  # Windows
+    fcntl = None
+
+from config import USER_CONFIG_PATH, get_config
+from logging_config import get_logger
+
+logger = get_logger("store")
+
+BACKENDS = ("sqlite", "json", "mongodb")
+
+
+def encode_value(value: Any) -> Any:
+    """JSON encoder fallback: datetimes become ISO strings."""
+    if isinstance(value, datetime):
+        return value.isoformat()
+    return str(value)
+
+
+def matches(document: Dict, filters: Dict[str, Any]) -> bool:
+    return all(document.get(field) == value for field, value in filters.items())
+
+
+class MetadataStore(ABC):
+    """
+    A collection of catalog documents, each identified by the value of its key field.
+
+    The catalog uses one store keyed by video_id and one keyed by path for
+    upload jobs. Documents are plain dicts; datetimes come back as ISO strings
+    from the embedded backends.
+    """
+
+    def __init__(self, name: str, key_field: str):
+        self.name = name
+        self.key_field = key_field
+
+    @abstractmethod
+    def list(self, **filters: Any) -> List[Dict]:
+        """Every document whose fields equal the given values, e.g. list(parent_id=video_id)."""
+
+    @abstractmethod
+    def get(self, key: str) -> Optional[Dict]:
+        """The document with this key, or None."""
+
+    @abstractmethod
+    def upsert(self, key: str, fields: Dict[str, Any]) -> None:
+        """Set fields on the document with this key, creating it if it doesn't exist."""
+
+    @abstractmethod
+    def delete(self, key: str) -> bool:
+        """Delete the document with this key, returning whether it existed."""
+
+    def query_by_hash(self, content_hash: str) -> List[Dict]:
+        """Documents for files with this content hash."""
+        return self.list(content_hash=content_hash)
+
+    def query_by_tag(self, tag: str) -> List[Dict]:
+        """Documents whose tags include this tag."""
+        return [document for document in self.list() if tag in document.get("tags", [])]
+
+
+class SQLiteStore(MetadataStore):
+    """Documents stored as JSON in a single SQLite file shared by every collection."""
+
+    def __init__(self, name: str, key_field: str, path: Path):
+        super().__init__(name, key_field)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        self.lock = threading.Lock()
+        self.connection = sqlite3.connect(str(path), check_same_thread=False)
+        with self.lock, self.connection:
+            self.connection.execute(
+                "CREATE TABLE IF NOT EXISTS documents "
+                "(collection TEXT NOT NULL, key TEXT NOT NULL, document TEXT NOT NULL, PRIMARY KEY (collection, key))"
+            )
+
+    def select(self, where: str = "", params: tuple = ()) -> List[Dict]:
+        with self.lock:
+            rows = self.connection.execute(
+                f"SELECT document FROM documents WHERE collection = ? {where} ORDER BY rowid", (self.name, *params)
+            ).fetchall()
+        return [json.loads(row[0]) for row in rows]
+
+    def list(self, **filters: Any) -> List[Dict]:
+        # Filter in SQL on json_extract where the value can be compared directly
+        where, params = "", []
+        for field, value in filters.items():
+            if isinstance(value, (str, int, float)) and not isinstance(value, bool):
+                where += f" AND json_extract(document, '$.{field}') = ?"
+                params.append(value)
+        return [document for document in self.select(where, tuple(params)) if matches(document, filters)]
+
+    def get(self, key: str) -> Optional[Dict]:
+        documents = self.select("AND key = ?", (key,))
+        return documents[0] if documents else None
+
+    def upsert(self, key: str, fields: Dict[str, Any]) -> None:
+        with self.lock, self.connection:
+            row = self.connection.execute(
+                "SELECT document FROM documents WHERE collection = ? AND key = ?", (self.name, key)
+            ).fetchone()
+            document = json.loads(row[0]) if row else {self.key_field: key}
+            document.update(json.loads(json.dumps(fields, default=encode_value)))
+            self.connection.execute(
+                "INSERT INTO documents (collection, key, document) VALUES (?, ?, ?) "
+                "ON CONFLICT (collection, key) DO UPDATE SET document = excluded.document",
+                (self.name, key, json.dumps(document)),
+            )
+
+    def delete(self, key: str) -> bool:
+        with self.lock, self.connection:
+            cursor = self.connection.execute(
+                "DELETE FROM documents WHERE collection = ? AND key = ?", (self.name, key)
+            )
+        return cursor.rowcount > 0
+
+    def query_by_tag(self, tag: str) -> List[Dict]:
+        return self.select(
+            "AND EXISTS (SELECT 1 FROM json_each(document, '$.tags') WHERE json_each.value = ?)", (tag,)
+        )
+
+
+class JSONFileStore(MetadataStore):
+    """
+    Documents stored in one JSON file per collection.
+
+    The file is read on every call, so other processes (e.g. a running
+    `watch`) see each other's changes, and replaced atomically on every write.
+    Writes hold an exclusive lock on a sidecar .lock file so concurrent
+    processes don't lose each other's updates; on platforms without fcntl
+    (Windows) only threads of one process are kept apart.
+    """
+
+    def __init__(self, name: str, key_field: str, directory: Path):
+        super().__init__(name, key_field)
+        self.path = directory / f"{name}.json"
+        self.lock_path = directory / f"{name}.lock"
+        self.lock = threading.Lock()
+
+    @contextmanager
+    def locked(self) -> Iterator[None]:
+        """Hold the collection's lock, across processes where possible, for a read-modify-write."""
+        with self.lock:
+            if fcntl is None:
+                yield
+                return
+            self.lock_path.parent.mkdir(parents=True, exist_ok=True)
+            with open(self.lock_path, "a") as lock_file:
+                fcntl.flock(lock_file, fcntl.LOCK_EX)
+                try:
+                    yield
+                finally:
+                    fcntl.flock(lock_file, fcntl.LOCK_UN)
+
+    def read(self) -> Dict[str, Dict]:
+        if not self.path.exists():
+            return {}
+        with open(self.path) as f:
+            return json.load(f)
+
+    def write(self, documents: Dict[str, Dict]) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        temp_path = self.path.with_suffix(".tmp")
+        with open(temp_path, "w") as f:
+            json.dump(documents, f, indent=2, default=encode_value)
+        os.replace(temp_path, self.path)
+
+    def list(self, **filters: Any) -> List[Dict]:
+        with self.lock:
+            return [document for document in self.read().values() if matches(document, filters)]
+
+    def get(self, key: str) -> Optional[Dict]:
+        with self.lock:
+            return self.read().get(key)
+
+    def upsert(self, key: str, fields: Dict[str, Any]) -> None:
+        with self.locked():
+            documents = self.read()
+            documents.setdefault(key, {self.key_field: key}).update(fields)
+            self.write(documents)
+
+    def delete(self, key: str) -> bool:
+        with self.locked():
+            documents = self.read()
+            if documents.pop(key, None) is None:
+                return False
+            self.write(documents)
+            return True
+
+
+class MongoStore(MetadataStore):
+    """Documents in a MongoDB collection."""
+
+    def __init__(self, name: str, key_field: str, collection: Any):
+        super().__init__(name, key_field)
+        self.collection = collection
+
+    def list(self, **filters: Any) -> List[Dict]:
+        return list(self.collection.find(filters, {"_id": 0}))
+
+    def get(self, key: str) -> Optional[Dict]:
+        return self.collection.find_one({self.key_field: key}, {"_id": 0})
+
+    def upsert(self, key: str, fields: Dict[str, Any]) -> None:
+        self.collection.update_one({self.key_field: key}, {"$set": fields}, upsert=True)
+
+    def delete(self, key: str) -> bool:
+        return self.collection.delete_one({self.key_field: key}).deleted_count > 0
+
+    def query_by_tag(self, tag: str) -> List[Dict]:
+        return list(self.collection.find({"tags": tag}, {"_id": 0}))
+
+
+_mongo_database = None
+
+
+def mongo_database(catalog_config: Dict) -> Any:
+    """Connect to MongoDB on first use; pymongo is only needed for this backend."""
+    global _mongo_database
+    if _mongo_database is None:
+        try:
+            from pymongo import MongoClient
+        except ImportError:
+            raise RuntimeError("catalog.backend is mongodb but pymongo is not installed (pip install pymongo)")
+        uri = catalog_config["mongo_uri"] or os.getenv("MONGO_URI")
+        if not uri:
+            raise RuntimeError("catalog.backend is mongodb but neither catalog.mongo_uri nor MONGO_URI is set")
+        _mongo_database = MongoClient(uri)[catalog_config["mongo_database"]]
+        logger.debug(f"Connected to MongoDB database: {_mongo_database.name}")
+    return _mongo_database
+
+
+def catalog_path(backend: str) -> Path:
+    """The catalog file (sqlite) or folder (json): catalog.path, or next to the user config."""
+    configured = get_config()["catalog"]["path"]
+    if configured:
+        return Path(os.path.expanduser(configured))
+    return USER_CONFIG_PATH.parent / ("catalog.db" if backend == "sqlite" else "catalog")
+
+
+def open_store(name: str, key_field: str) -> MetadataStore:
+    """
+    Open a catalog collection with the configured backend.
+
+    Args:
+        name (str): Collection name, e.g. "metadata" or "clientA_jobs"
+        key_field (str): The document field that identifies each document
+    """
+    catalog_config = get_config()["catalog"]
+    backend = catalog_config["backend"]
+    if backend == "sqlite":
+        return SQLiteStore(name, key_field, catalog_path(backend))
+    if backend == "json":
+        return JSONFileStore(name, key_field, catalog_path(backend))
+    if backend == "mongodb":
+        return MongoStore(name, key_field, mongo_database(catalog_config)[name])
+    raise ValueError(f"Unknown catalog.backend {backend!r}; use one of: {', '.join(BACKENDS)}")
@@ -0,0 +1,58 @@
+import unittest
+
+from chunks import plan_chunks, translate_clips
+
+
+class PlanChunksTests(unittest.TestCase):
+    def test_short_file_is_one_chunk(self):
+        self.assertEqual(plan_chunks(30.0, 40.0, 5.0), [(0.0, 30.0)])
+
+    def test_chunks_overlap_and_last_chunk_is_shortened(self):
+        self.assertEqual(plan_chunks(100.0, 40.0, 5.0), [(0.0, 40.0), (35.0, 40.0), (70.0, 30.0)])
+
+    def test_no_extra_chunk_when_the_last_one_ends_exactly_at_the_end(self):
+        self.assertEqual(plan_chunks(75.0, 40.0, 5.0), [(0.0, 40.0), (35.0, 40.0)])
+
+    def test_chunks_cover_the_whole_duration(self):
+        chunks = plan_chunks(7200.5, 3600.0, 30.0)
+        start, length = chunks[-1]
+        self.assertEqual(start + length, 7200.5)
+        for (start, length), (next_start, _) in zip(chunks, chunks[1:]):
+            self.assertEqual(start + length - next_start, 30.0)
+
+
+class TranslateClipsTests(unittest.TestCase):
+    chunks = {
+        "c0": {"parent_id": "p", "offset": 0.0},
+        "c1": {"parent_id": "p", "offset": 35.0},
+    }
+
+    def test_chunk_hits_move_to_the_parent_timeline(self):
+        clips = translate_clips([{"video_id": "c1", "start_time": 10.0, "end_time": 12.5, "score": 0.7}], self.chunks)
+        self.assertEqual(
+            clips, [{"video_id": "p", "start_time": 45.0, "end_time": 47.5, "score": 0.7, "chunk_video_id": "c1"}]
+        )
+
+    def test_other_videos_are_left_alone(self):
+        clip = {"video_id": "v1", "start_time": 1.0, "end_time": 2.0, "score": 0.5}
+        self.assertEqual(translate_clips([clip], self.chunks), [clip])
+
+    def test_hit_found_in_both_overlapping_chunks_is_kept_once_with_the_better_score(self):
+        clips = translate_clips([
+            {"video_id": "c0", "start_time": 36.0, "end_time": 38.0, "score": 0.5},
+            {"video_id": "c1", "start_time": 1.2, "end_time": 3.1, "score": 0.8},
+        ], self.chunks)
+        self.assertEqual(len(clips), 1)
+        self.assertEqual(clips[0]["chunk_video_id"], "c1")
+        self.assertEqual(clips[0]["score"], 0.8)
+
+    def test_hits_further_apart_than_the_tolerance_are_both_kept(self):
+        clips = translate_clips([
+            {"video_id": "c0", "start_time": 36.0, "end_time": 38.0, "score": 0.5},
+            {"video_id": "c1", "start_time": 4.0, "end_time": 6.0, "score": 0.8},
+        ], self.chunks)
+        self.assertEqual([clip["start_time"] for clip in clips], [36.0, 39.0])
+
+
+if __name__ == "__main__":
+    unittest.main()
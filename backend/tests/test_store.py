@@ -0,0 +1,113 @@
+import multiprocessing
+import tempfile
+import unittest
+from datetime import datetime
+from pathlib import Path
+
+from store import JSONFileStore, SQLiteStore
+
+
+class StoreTests:
+    """Behaviour shared by the embedded backends; subclasses provide make_store."""
+
+    def make_store(self, name: str, key_field: str):
+        raise NotImplementedError
+
+    def setUp(self):
+        self.directory = tempfile.TemporaryDirectory()
+        self.root = Path(self.directory.name)
+        self.store = self.make_store("metadata", "video_id")
+
+    def tearDown(self):
+        self.directory.cleanup()
+
+    def test_get_missing(self):
+        self.assertIsNone(self.store.get("v1"))
+
+    def test_upsert_creates_and_merges(self):
+        self.store.upsert("v1", {"original_path": "/a.mp4", "size": 10})
+        self.store.upsert("v1", {"size": 20, "shots": [1.5, 3.0]})
+        self.assertEqual(
+            self.store.get("v1"), {"video_id": "v1", "original_path": "/a.mp4", "size": 20, "shots": [1.5, 3.0]}
+        )
+
+    def test_datetimes_are_stored_as_iso_strings(self):
+        uploaded_at = datetime(2025, 1, 2, 3, 4, 5)
+        self.store.upsert("v1", {"uploaded_at": uploaded_at})
+        self.assertEqual(self.store.get("v1")["uploaded_at"], uploaded_at.isoformat())
+
+    def test_list_filters_on_equal_fields(self):
+        self.store.upsert("v1", {"parent_id": "p", "chunk_index": 0})
+        self.store.upsert("v2", {"parent_id": "p", "chunk_index": 1})
+        self.store.upsert("v3", {"parent_id": "q", "chunk_index": 0})
+        self.store.upsert("v4", {"duplicate": True})
+        self.assertEqual([doc["video_id"] for doc in self.store.list()], ["v1", "v2", "v3", "v4"])
+        self.assertEqual([doc["video_id"] for doc in self.store.list(parent_id="p")], ["v1", "v2"])
+        self.assertEqual([doc["video_id"] for doc in self.store.list(parent_id="p", chunk_index=1)], ["v2"])
+        self.assertEqual([doc["video_id"] for doc in self.store.list(duplicate=True)], ["v4"])
+        self.assertEqual(self.store.list(parent_id="missing"), [])
+
+    def test_delete(self):
+        self.store.upsert("v1", {})
+        self.assertTrue(self.store.delete("v1"))
+        self.assertFalse(self.store.delete("v1"))
+        self.assertIsNone(self.store.get("v1"))
+
+    def test_query_by_hash(self):
+        self.store.upsert("v1", {"content_hash": "abc", "size": 10})
+        self.store.upsert("v2", {"content_hash": "def", "size": 10})
+        self.assertEqual([doc["video_id"] for doc in self.store.query_by_hash("abc")], ["v1"])
+        self.assertEqual(self.store.query_by_hash("missing"), [])
+
+    def test_query_by_tag(self):
+        self.store.upsert("v1", {"tags": ["launch", "b-roll"]})
+        self.store.upsert("v2", {"tags": ["b-roll"]})
+        self.store.upsert("v3", {})
+        self.assertEqual([doc["video_id"] for doc in self.store.query_by_tag("b-roll")], ["v1", "v2"])
+        self.assertEqual([doc["video_id"] for doc in self.store.query_by_tag("launch")], ["v1"])
+        self.assertEqual(self.store.query_by_tag("missing"), [])
+
+    def test_collections_are_separate(self):
+        jobs = self.make_store("jobs", "path")
+        self.store.upsert("v1", {})
+        jobs.upsert("/a.mp4", {"status": "ready"})
+        self.assertEqual(jobs.list(), [{"path": "/a.mp4", "status": "ready"}])
+        self.assertEqual(self.store.list(), [{"video_id": "v1"}])
+
+    def test_documents_persist_across_instances(self):
+        self.store.upsert("v1", {"size": 10})
+        self.assertEqual(self.make_store("metadata", "video_id").get("v1"), {"video_id": "v1", "size": 10})
+
+
+class SQLiteStoreTests(StoreTests, unittest.TestCase):
+    def make_store(self, name, key_field):
+        return SQLiteStore(name, key_field, self.root / "catalog.db")
+
+    def tearDown(self):
+        self.store.connection.close()
+        super().tearDown()
+
+
+def upsert_many(directory: str, worker: int) -> None:
+    store = JSONFileStore("metadata", "video_id", Path(directory))
+    for i in range(20):
+        store.upsert(f"v{worker}_{i}", {"worker": worker})
+
+
+class JSONFileStoreTests(StoreTests, unittest.TestCase):
+    def make_store(self, name, key_field):
+        return JSONFileStore(name, key_field, self.root / "catalog")
+
+    def test_concurrent_processes_keep_every_write(self):
+        processes = [
+            multiprocessing.Process(target=upsert_many, args=(str(self.root / "catalog"), worker)) for worker in range(4)
+        ]
+        for process in processes:
+            process.start()
+        for process in processes:
+            process.join()
+        self.assertEqual(len(self.store.list()), 80)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,55 @@
+from pathlib import Path
+from typing import Dict, Optional
+
+import ffmpeg
+
+from config import get_config
+from edit_generator import seconds_to_timestamp
+from logging_config import get_logger
+
+logger = get_logger("thumbnails")
+
+# Seek this far back from a clip's end so the frame still belongs to the clip
+END_OFFSET = 0.05
+
+
+def thumbnail_path(thumbnails_dir: Path, video_id: str, seconds: float) -> Path:
+    """Where the thumbnail of a video at a given time is cached."""
+    return thumbnails_dir / video_id / f"{seconds_to_timestamp(seconds).replace(':', '-')}.jpg"
+
+
+def extract_thumbnail(source_path: str, seconds: float, output_path: Path) -> Optional[str]:
+    """Extract a single frame as a JPEG, reusing the cached file if it already exists."""
+    if output_path.exists():
+        return str(output_path)
+    output_path.parent.mkdir(parents=True, exist_ok=True)
+    stream = ffmpeg.input(source_path, ss=max(seconds, 0.0))
+    stream = ffmpeg.output(stream, str(output_path), vframes=1, vf=f"scale={get_config()['thumbnails']['width']}:-2")
+    try:
+        ffmpeg.run(stream, overwrite_output=True, quiet=True)
+    except ffmpeg.Error as e:
+        logger.debug(f"Could not extract a thumbnail from {source_path} at {seconds}s: {str(e)}")
+        return None
+    return str(output_path) if output_path.exists() else None
+
+
+def clip_thumbnails(clip: Dict, source_path: str, thumbnails_dir: Path) -> Dict[str, str]:
+    """
+    Extract thumbnails at a clip's start, midpoint and end.
+
+    Returns:
+        Dict[str, str]: Thumbnail paths keyed by "start", "mid" and "end";
+        frames that could not be extracted are left out
+    """
+    start, end = clip["start_time"], clip["end_time"]
+    times = {
+        "start": start,
+        "mid": (start + end) / 2,
+        "end": max(end - END_OFFSET, start),
+    }
+    thumbnails = {}
+    for position, seconds in times.items():
+        path = extract_thumbnail(source_path, seconds, thumbnail_path(thumbnails_dir, clip["video_id"], seconds))
+        if path:
+            thumbnails[position] = path
+    return thumbnails
@@ -1,9 +1,10 @@
 import os
 from twelvelabs import TwelveLabs
 from glob import glob
-from twelvelabs.models.task import Task
 from twelvelabs.models.search import SearchData, GroupByVideoSearchData
 from dotenv import load_dotenv
+from logging_config import get_logger
+from config import get_config
 
 # Load environment variables from .env file
 load_dotenv()
@@ -12,6 +13,7 @@ INDEX_ID = os.getenv("INDEX_ID")
 GEMINI_API_KEY = os.getenv("GEMINI_API_KEY")
 
 client = TwelveLabs(api_key=os.getenv("TL_API_KEY"))
+logger = get_logger("twelve")
 
 
 def validate_video_path(video_path):
@@ -23,23 +25,61 @@ def validate_video_path(video_path):
         raise FileNotFoundError(f"No videos found in the path {video_path}.")
     return video_path
 
-def upload_video(video_path):
+# HTTP statuses worth retrying: timeouts, rate limits and server errors
+TRANSIENT_STATUS_CODES = {408, 429, 500, 502, 503, 504}
+
+def error_status_code(error):
+    """The HTTP status of a failed API call, if it has one."""
+    return getattr(error, "status_code", None) or getattr(getattr(error, "response", None), "status_code", None)
+
+def is_transient_error(error):
+    """Check whether a failed API call is worth retrying."""
+    if isinstance(error, (ConnectionError, TimeoutError)):
+        return True
+    if error_status_code(error) in TRANSIENT_STATUS_CODES:
+        return True
+    name = type(error).__name__
+    return any(word in name for word in ("Timeout", "Connection", "RateLimit", "InternalServer"))
+
+def create_index(name):
+    """Create a Twelvelabs index for visual and audio search, returning its id."""
+    engine = get_config()["models"]["index"]
+    index = client.index.create(name=name, models=[{"name": engine, "options": ["visual", "audio"]}])
+    logger.info(f"Created index {name} ({index.id}) with {engine}")
+    return index.id
+
+def create_upload_task(video_path, index_id=None):
+    """Start uploading a video for indexing without waiting for it, returning the task id."""
     validated_path = validate_video_path(video_path)
-    task = client.task.create(index_id=INDEX_ID, file=validated_path)
-    print(f"Task id={task.id}")
-    # (Optional) Monitor the video indexing process
-    # Utility function to print the status of a video indexing task
-    def on_task_update(task: Task):
-        print(f"  Status={task.status}")
-
-    task.wait_for_done(callback=on_task_update)
-    if task.status != "ready":
-        raise RuntimeError(f"Indexing failed with status {task.status}")
-    print(
-        f"Uploaded {validated_path}. The unique identifer of your video is {task.video_id}."
-    )
-    video_id = task.video_id
-    return video_id
+    task = client.task.create(index_id=index_id or INDEX_ID, file=validated_path)
+    logger.info(f"Task id={task.id}")
+    return task.id
+
+def get_task(task_id):
+    """Fetch the current state of an indexing task."""
+    return client.task.retrieve(task_id)
+
+def list_index_videos(index_id=None, page_limit=50):
+    """List every video in the index, as dicts with video_id, filename and duration."""
+    videos = []
+    page = 1
+    while True:
+        batch = client.index.video.list(index_id or INDEX_ID, page=page, page_limit=page_limit)
+        for video in batch:
+            system_metadata = getattr(video, "system_metadata", None)
+            videos.append({
+                "video_id": video.id,
+                "filename": getattr(system_metadata, "filename", None),
+                "duration": getattr(system_metadata, "duration", None),
+            })
+        if len(batch) < page_limit:
+            return videos
+        page += 1
+
+def delete_index_video(video_id, index_id=None):
+    """Delete a video from the index."""
+    client.index.video.delete(index_id or INDEX_ID, video_id)
+    logger.info(f"Deleted video {video_id} from index {index_id or INDEX_ID}")
 
 def print_search_data(data: SearchData):
     return {
@@ -50,16 +90,18 @@ def print_search_data(data: SearchData):
         'thumbnail_url': data.thumbnail_url
     }
 
-def search_video(user_query):
+def search_video(user_query, index_id=None):
     user_query = user_query.strip()
     user_query = user_query.lower()
+    search_config = get_config()["search"]
+    min_score = search_config["min_score"]
     result = client.search.query(
-        index_id=INDEX_ID,
+        index_id=index_id or INDEX_ID,
         options=["visual", "audio"],
         query_text=user_query,
         group_by="clip",
         operator="or",
-        page_limit=5,
+        page_limit=search_config["page_limit"],
         sort_option="score",
     )
     
@@ -80,18 +122,17 @@ def search_video(user_query):
             highest_score = max(highest_score, clip_data['score'])
     
     # Filter clips based on score
-    if highest_score > 0.7:  # Consider scores above 0.7 as "high"
-        high_scored_clips = [clip for clip in all_clips if clip['score'] >= 0.7]
+    if highest_score > min_score:  # Consider scores above min_score as "high"
+        high_scored_clips = [clip for clip in all_clips if clip['score'] >= min_score]
         if high_scored_clips:
             all_clips = high_scored_clips
     
-    # Print results
+    # Log results
     for clip in all_clips:
-        print(f"\nClip Details:")
-        print(f"  Timestamp: {clip['start_time']} - {clip['end_time']}")
-        print(f"  Score: {clip['score']}")
-        print(f"  Thumbnail URL: {clip['thumbnail_url']}")
-        print(f"  Video ID: {clip['video_id']}")
+        logger.debug(
+            f"Clip Details: timestamp {clip['start_time']} - {clip['end_time']}, score {clip['score']}, "
+            f"thumbnail {clip['thumbnail_url']}, video {clip['video_id']}"
+        )
     
     return all_clips
 
@@ -0,0 +1,193 @@
+import asyncio
+import json
+import os
+import time
+from datetime import datetime
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from config import get_config
+from ingest import is_video_file
+from logging_config import get_logger
+
+logger = get_logger("watch")
+
+
+class FolderWatcher:
+    """
+    Find new videos in watched folders once they have stopped growing.
+
+    A file is ready when its size and modification time have not changed for
+    settle_seconds, so videos still being copied or recorded are left alone.
+    A file that changes after it was handed out is picked up again.
+    """
+
+    def __init__(self, folders: List[str], settle_seconds: float, recursive: bool, extensions: List[str]):
+        self.folders = [Path(os.path.expanduser(folder)).resolve() for folder in folders]
+        self.settle_seconds = settle_seconds
+        self.recursive = recursive
+        self.extensions = [ext.lower() for ext in extensions]
+        self.seen: Dict[str, Tuple[int, float, float]] = {}  # path -> (size, mtime, unchanged since)
+        self.handled: Dict[str, Tuple[int, float]] = {}  # path -> (size, mtime) when handed out
+
+    def list_videos(self) -> List[Path]:
+        videos = []
+        for folder in self.folders:
+            if not folder.is_dir():
+                continue
+            candidates = folder.rglob("*") if self.recursive else folder.iterdir()
+            videos.extend(path for path in candidates if is_video_file(path, self.extensions))
+        return sorted(videos)
+
+    def skip(self, paths: List[str]) -> None:
+        """Treat files as already handled, e.g. because they are in the catalog."""
+        for path in paths:
+            try:
+                stat = os.stat(path)
+            except OSError:
+                continue
+            self.handled[path] = (stat.st_size, stat.st_mtime)
+
+    def scan(self) -> Tuple[List[str], List[str]]:
+        """
+        Check the watched folders once.
+
+        Returns:
+            Tuple[List[str], List[str]]: Files that have settled and should be
+            uploaded, and files that are still changing
+        """
+        now = time.monotonic()
+        ready, settling = [], []
+        present = set()
+        for video in self.list_videos():
+            path = str(video)
+            present.add(path)
+            try:
+                stat = video.stat()
+            except OSError:
+                continue
+            signature = (stat.st_size, stat.st_mtime)
+            if self.handled.get(path) == signature:
+                continue
+
+            previous = self.seen.get(path)
+            if not previous or previous[:2] != signature:
+                self.seen[path] = (*signature, now)
+                settling.append(path)
+            elif now - previous[2] >= self.settle_seconds:
+                del self.seen[path]
+                self.handled[path] = signature
+                ready.append(path)
+            else:
+                settling.append(path)
+
+        # Forget files that were moved or deleted before they settled
+        for path in list(self.seen):
+            if path not in present:
+                del self.seen[path]
+        return ready, settling
+
+
+class WatchStatus:
+    """A JSON status file describing the watcher, rewritten atomically so other tools can poll it."""
+
+    def __init__(self, path: Path, folders: List[str]):
+        self.path = path
+        self.status = {
+            "pid": os.getpid(),
+            "state": "starting",
+            "folders": folders,
+            "started_at": datetime.utcnow().isoformat(),
+            "updated_at": None,
+            "settling": [],
+            "files": {},
+        }
+
+    def update(self, state: Optional[str] = None, settling: Optional[List[str]] = None) -> None:
+        if state:
+            self.status["state"] = state
+        if settling is not None:
+            self.status["settling"] = settling
+        self.write()
+
+    def record(self, path: str, status: str, video_id: Optional[str] = None, error: Optional[str] = None) -> None:
+        """Record the latest state of one file."""
+        self.status["files"][path] = {
+            "status": status,
+            "video_id": video_id,
+            "error": error,
+            "updated_at": datetime.utcnow().isoformat(),
+        }
+
+    def write(self) -> None:
+        self.status["updated_at"] = datetime.utcnow().isoformat()
+        counts: Dict[str, int] = {}
+        for entry in self.status["files"].values():
+            counts[entry["status"]] = counts.get(entry["status"], 0) + 1
+        self.status["counts"] = counts
+        try:
+            self.path.parent.mkdir(parents=True, exist_ok=True)
+            temp_path = self.path.with_suffix(".tmp")
+            with open(temp_path, "w") as f:
+                json.dump(self.status, f, indent=2)
+            os.replace(temp_path, self.path)
+        except OSError as e:
+            logger.warning(f"Could not write watch status to {self.path}: {str(e)}")
+
+
+def status_file_path() -> Path:
+    """The configured watch status file, defaulting to watch_status.json in the temp directory."""
+    config = get_config()
+    configured = config["watch"]["status_file"]
+    return Path(configured) if configured else Path(config["paths"]["temp_dir"]) / "watch_status.json"
+
+
+async def watch_folders(editor, folders: Optional[List[str]] = None, recursive: bool = False, once: bool = False) -> Dict:
+    """
+    Upload and index new videos as they appear in the watched folders.
+
+    Args:
+        editor (VideoEditor): Runs the upload pipeline for each batch of settled files
+        folders (List[str], optional): Folders to watch (default: watch.folders from config)
+        recursive (bool): Also watch subfolders (default: upload.recursive from config)
+        once (bool): Exit once every file found has been uploaded instead of watching forever
+
+    Returns:
+        Dict: The final contents of the status file
+    """
+    config = get_config()
+    folders = folders or config["watch"]["folders"]
+    watcher = FolderWatcher(
+        folders,
+        settle_seconds=config["watch"]["settle_seconds"],
+        recursive=recursive or config["upload"]["recursive"],
+        extensions=config["upload"]["extensions"],
+    )
+    status = WatchStatus(status_file_path(), [str(folder) for folder in watcher.folders])
+    for folder in watcher.folders:
+        if not folder.is_dir():
+            logger.warning(f"Watched folder does not exist yet: {folder}")
+
+    # Files that are already indexed are not uploaded again
+    watcher.skip([video["original_path"] for video in editor.list_uploaded_videos() if video.get("original_path")])
+    logger.info(f"Watching {', '.join(str(folder) for folder in watcher.folders)} for new videos (Ctrl+C to stop)")
+    logger.info(f"Status file: {status.path}")
+
+    try:
+        while True:
+            ready, settling = watcher.scan()
+            if ready:
+                for path in ready:
+                    status.record(path, "uploading")
+                status.update("uploading", settling)
+                logger.info(f"Found {len(ready)} new video(s)")
+                for upload in await editor.upload_videos(ready):
+                    status.record(upload["path"], upload["status"], upload["video_id"], upload["error"])
+                continue
+            status.update("settling" if settling else "idle", settling)
+            if once and not settling:
+                break
+            await asyncio.sleep(config["watch"]["interval"])
+    finally:
+        status.update("stopped", [])
+    return status.status